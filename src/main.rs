@@ -1,6 +1,7 @@
 #![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
 
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::{Arc, Mutex, OnceLock};
 
 use notify_rust::Notification;
@@ -20,7 +21,6 @@ use settings::AppSettings;
 use user_data::UserData;
 use utils::list_system_fonts;
 
-mod bitstream_converter;
 mod check_update;
 mod manager;
 mod settings;
@@ -70,13 +70,23 @@ fn main() {
     fav_manager.initialize();
     fav_manager.connect_callbacks();
 
-    let schedule_manager = ScheduleManager::new(main_window.as_weak(), Arc::new(song_manager));
+    let database = Arc::new(TantivySink::from(data_manager.data_dir(&["index"])));
+
+    set_cache_dirs(&data_manager);
+    let schedule_manager = ScheduleManager::new(
+        main_window.as_weak(),
+        Arc::new(song_manager),
+        database.clone(),
+        data_manager.clone(),
+    );
     schedule_manager.initialize();
     schedule_manager.connect_callbacks();
 
-    let media_manager = Arc::new(MediaManager::new(
+    // holds slint timers and images, so it never leaves the UI thread
+    let media_manager = Rc::new(MediaManager::new(
         &main_window,
         &view_window,
+        schedule_manager.clone(),
         data_manager.clone(),
     ));
     let bibles_manager = Arc::new(OnceLock::<BiblesManager>::new());
@@ -84,7 +94,6 @@ fn main() {
     let cache_dir = data_manager.data_dir(&["cache"]);
     let need_update = check_for_updates(&cache_dir);
 
-    let database = Arc::new(TantivySink::from(data_manager.data_dir(&["index"])));
     let source_variants = setup_core::SetupBuilder::new().cache_path(cache_dir)
         // Add Reina Valera 1960 Bible
         .add_bible_from_url(
@@ -386,13 +395,16 @@ fn main() {
     main_window.on_send_to_view({
         let view_window = view_window.as_weak();
         let main_window = main_window.as_weak();
+        let media_manager = media_manager.clone();
         move || {
             let view_window = view_window.unwrap();
             let main_window = main_window.unwrap();
             let main_state = main_window.global::<ViewState>();
             let view_state = view_window.global::<ViewState>();
 
-            view_state.set_shared_view(main_state.get_shared_view());
+            view_state.invoke_begin_transition(main_state.get_shared_view());
+            media_manager.forget_hidden_text();
+            media_manager.stop_replaced_video();
         }
     });
 
@@ -428,51 +440,81 @@ fn main() {
         }
     });
 
-    main_window.on_shutdown_output({
+    main_window.on_clear_image({
+        let main_window = main_window.as_weak();
         let view_window = view_window.as_weak();
+        let media_manager = media_manager.clone();
         move || {
+            media_manager.stop_preview_video();
+            media_manager.stop_output_video();
             let view_window = view_window.unwrap();
+            let main_window = main_window.unwrap();
             let state = view_window.global::<ViewState>();
 
-            state.set_off(!state.get_off());
+            let mut background = state.get_background();
+            background.show_img = false;
+            state.set_background(background);
+
+            let main_state = main_window.global::<ViewState>();
+            let mut shared = main_state.get_shared_view();
+            shared.show_img = false;
+            main_state.set_shared_view(shared);
         }
     });
 
-    main_window.on_clear_image({
-        let main_window = main_window.as_weak();
+    main_window.on_end_output_media({
         let view_window = view_window.as_weak();
         let media_manager = media_manager.clone();
         move || {
-            media_manager.stop_preview_video();
             media_manager.stop_output_video();
             let view_window = view_window.unwrap();
-            let main_window = main_window.unwrap();
             let state = view_window.global::<ViewState>();
 
-            let mut shared = state.get_shared_view();
-            shared.show_img = false;
-
-            state.set_shared_view(shared.clone());
-            main_window.global::<ViewState>().set_shared_view(shared);
+            let mut background = state.get_background();
+            background.show_img = false;
+            state.set_background(background);
         }
     });
 
     main_window.on_clear_output({
         let main_window = main_window.as_weak();
         let view_window = view_window.as_weak();
+        let media_manager = media_manager.clone();
         move || {
             let view_window = view_window.unwrap();
             let main_window = main_window.unwrap();
             let state = view_window.global::<ViewState>();
 
-            let mut shared = state.get_shared_view();
+            let mut text = state.get_text();
+            text.content = SharedString::default();
+            text.verse = SharedString::default();
+            state.set_text(text);
+
+            let main_state = main_window.global::<ViewState>();
+            let mut shared = main_state.get_shared_view();
             shared.content = SharedString::default();
             shared.verse = SharedString::default();
+            main_state.set_shared_view(shared);
 
-            state.set_shared_view(shared.clone());
-            main_window.global::<ViewState>().set_shared_view(shared);
+            media_manager.forget_hidden_text();
+        }
+    });
 
-            state.set_off(false);
+    main_window.global::<ViewState>().on_toggle_alert({
+        let main_window = main_window.as_weak();
+        let view_window = view_window.as_weak();
+        move |text| {
+            let main_window = main_window.unwrap();
+            let main_state = main_window.global::<ViewState>();
+            let mut alert = main_state.get_alert();
+            alert.visible = !alert.visible && !text.trim().is_empty();
+            alert.text = text.trim().into();
+
+            view_window
+                .unwrap()
+                .global::<ViewState>()
+                .set_alert(alert.clone());
+            main_state.set_alert(alert);
         }
     });
 
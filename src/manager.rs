@@ -6,6 +6,9 @@ mod song;
 
 pub use bibles::BiblesManager;
 pub use fav_text::FavTextManager;
-pub use media::{MediaItem, MediaManager};
-pub use schedule::ScheduleManager;
+pub use media::{
+    MediaItem, MediaManager, document_pages, document_slides, is_document,
+    render_pages_in_background, set_cache_dirs,
+};
+pub use schedule::{ScheduleFile, ScheduleManager};
 pub use song::SongsManager;
@@ -1,6 +1,6 @@
-use std::fs::File;
-use std::io::BufReader;
-use std::path::PathBuf;
+use std::cell::{Cell, RefCell};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
@@ -8,30 +8,46 @@ use std::time::{Duration, Instant};
 
 use futures::StreamExt;
 use gst::glib::object::ObjectExt;
-use gst::prelude::{ElementExt, ElementExtManual, GstBinExt};
+use gst::prelude::{
+    DeviceExt, DeviceMonitorExt, DeviceMonitorExtManual, ElementExt, ElementExtManual, GstBinExt,
+};
 use gst::{MessageView, Pipeline};
-use mp4::Mp4Reader;
-use openh264::OpenH264API;
-use openh264::decoder::{Decoder, DecoderConfig, Flush};
 use rfd::FileDialog;
 use slint::winit_030::winit::event::WindowEvent;
 use slint::winit_030::{EventResult, WinitWindowAccessor};
 use slint::{
-    Color, ComponentHandle, Image, ModelRc, Rgb8Pixel, SharedPixelBuffer, SharedString,
-    ToSharedString, Weak,
+    Color, ComponentHandle, Image, Model, ModelRc, SharedString, TimerMode, ToSharedString, Weak,
 };
 use tracing::error;
-use ui::{MainWindow, ViewData, ViewFontData, ViewState, ViewWindow};
+use ui::{
+    MainWindow, ScheduleState, Settings, Transition, VideoEnd, ViewData, ViewFontData, ViewState,
+    ViewWindow,
+};
 
-use crate::bitstream_converter::Mp4BitstreamConverter;
-use crate::settings::SourceMedia;
+use crate::manager::ScheduleManager;
+use crate::settings::{AppSettings, MediaFolders, SourceMedia};
 use crate::user_data::UserData;
 
+mod branding;
+mod document;
 #[cfg(target_os = "linux")]
 mod egl;
+mod fade;
+mod filter;
 mod init;
+mod library;
+mod relink;
+mod slideshow;
 #[cfg(not(target_os = "linux"))]
 mod software;
+mod thumbnail;
+
+pub use document::{document_pages, document_slides, is_document, render_pages_in_background};
+
+thread_local! {
+    // every library tile in list order, filtering picks from these without loading images again
+    static TILES: RefCell<Vec<ViewData>> = const { RefCell::new(Vec::new()) };
+}
 
 const IMAGE_FORMATS: &[&str] = &[
     "avif", "bmp", "dds", "exr", "ff", "gif", "hdr", "ico", "jpeg", "jpg", "png", "pnm", "qoi",
@@ -41,14 +57,25 @@ const ALL_MEDIA_FORMATS: &[&str] = &[
     // Image formats
     "avif", "bmp", "dds", "exr", "ff", "gif", "hdr", "ico", "jpeg", "jpg", "png", "pnm", "qoi",
     "tga", "tiff", "tif", "webp", // Video formats
-    "mov", "mp4", "m4a", "m4v", "m4b", "m4r", "m4p", "3gp", "3g2", "mj2", "qt",
+    "mov", "mp4", "m4a", "m4v", "m4b", "m4r", "m4p", "3gp", "3g2", "mj2", "qt", "mkv", "webm",
+    "avi", "wmv", "mpg", "mpeg", "ts", "ogv", "flv",
 ];
 
+/// Compares the lowercased extension, cameras often write `.JPG` or `.MP4`.
+fn has_extension(path: &Path, formats: &[&str]) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| formats.contains(&e.to_lowercase().as_str()))
+}
+
 pub struct MediaManager {
     data: Arc<UserData>,
+    schedule: ScheduleManager,
     window: Weak<MainWindow>,
     view_window: Weak<ViewWindow>,
     media_list: Arc<Mutex<SourceMedia>>,
+    media_folders: Mutex<MediaFolders>,
+    watcher: Mutex<notify::RecommendedWatcher>,
 
     preview_video_playing: Arc<AtomicBool>,
     output_video_playing: Arc<AtomicBool>,
@@ -56,6 +83,22 @@ pub struct MediaManager {
     pipeline: gst::Pipeline,
     sink_element: gst::Element,
     current_playbin: Arc<Mutex<Option<gst::Element>>>,
+    // audio device picked in the settings, None for the default one
+    audio_device: RefCell<Option<gst::Device>>,
+    output_end: Rc<Cell<VideoEnd>>,
+    // (muted, volume) of the item on output, before the master volume
+    output_audio: Cell<(bool, f32)>,
+    // what was last sent to the output, kept to bring it back after a logo video
+    output_media: RefCell<Option<ViewData>>,
+    // where the replayed output video resumes once its playbin prerolled
+    resume_at: Rc<Cell<Option<gst::ClockTime>>>,
+    blanked: RefCell<Option<fade::Blanked>>,
+    // media id of the logo picked for the logo action
+    active_logo: RefCell<Option<i32>>,
+    // advances the images of a slideshow on output
+    slideshow: Rc<slint::Timer>,
+    // writes the master volume once the slider settles
+    volume_save: slint::Timer,
 
     preview_enabled: Arc<AtomicBool>,
     output_enabled: Arc<AtomicBool>,
@@ -63,7 +106,13 @@ pub struct MediaManager {
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct MediaItem {
+    // unique in the media list, 0 until one is assigned
+    #[serde(default)]
+    pub id: i32,
     tmp: bool,
+    // found in a watched folder, never saved
+    #[serde(skip)]
+    pub watched: bool,
     #[serde(default)]
     pub is_logo: bool,
     pub path: Option<String>,
@@ -73,6 +122,66 @@ pub struct MediaItem {
     pub font: FontData,
     #[serde(default)]
     pub verse_font: FontData,
+    #[serde(default)]
+    pub video_end: VideoEnd,
+    #[serde(default)]
+    pub muted: bool,
+    #[serde(default = "full_volume")]
+    pub volume: f32,
+    #[serde(default)]
+    pub transition: Transition,
+    #[serde(default)]
+    pub transition_ms: i32,
+    // bytes, used to recognise the file once it has been moved
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // a slideshow when not empty, `path` holds the first image
+    #[serde(default)]
+    pub images: Vec<String>,
+    #[serde(default)]
+    pub image_seconds: i32,
+}
+
+impl MediaItem {
+    /// Gives every item without an id the next free one, returns whether any was given.
+    pub fn assign_ids(media_list: &mut SourceMedia) -> bool {
+        let first = Self::next_id(media_list);
+        let mut assigned = false;
+        for (id, item) in (first..).zip(media_list.iter_mut().filter(|m| m.id == 0)) {
+            item.id = id;
+            assigned = true;
+        }
+        assigned
+    }
+
+    pub fn next_id(media_list: &SourceMedia) -> i32 {
+        media_list.iter().map(|m| m.id).max().unwrap_or(0) + 1
+    }
+
+    pub fn is_missing(&self) -> bool {
+        self.files().any(|p| !Path::new(p).exists())
+    }
+
+    /// Every file the item shows, the slideshow images included.
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        // a document's pages are rendered from it, earlier lists stored them as images
+        let images = match self.path.as_deref() {
+            Some(path) if document::is_document(path) => &[][..],
+            _ => self.images.as_slice(),
+        };
+        self.path
+            .as_deref()
+            .into_iter()
+            .chain(images.iter().map(String::as_str))
+    }
+}
+
+fn full_volume() -> f32 {
+    1.0
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
@@ -166,24 +275,46 @@ impl<'a> From<&'a MediaItem> for ViewData {
             font_size: value.verse_font.font_size,
         };
 
+        let images = value
+            .path
+            .as_deref()
+            .and_then(document_pages)
+            .unwrap_or_else(|| value.images.iter().map(|i| i.to_shared_string()).collect());
+
         Self {
+            id: value.id,
             tmp: value.tmp,
+            watched: value.watched,
             is_logo: value.is_logo,
             path: value.path.clone().unwrap_or_default().to_shared_string(),
-            show_img: ALL_MEDIA_FORMATS
-                .iter()
-                .any(|f| value.path.clone().unwrap_or_default().ends_with(f)),
+            show_img: !images.is_empty()
+                || value
+                    .path
+                    .as_deref()
+                    .is_some_and(|p| has_extension(Path::new(p), ALL_MEDIA_FORMATS)),
             color,
             content: SharedString::default(),
             verse: SharedString::default(),
-            img_bg: value
-                .path
-                .as_deref()
-                .and_then(MediaManager::generate_video_thumbnail)
+            // a document shows its first page
+            img_bg: images
+                .first()
+                .map(SharedString::as_str)
+                .or(value.path.as_deref())
+                .and_then(MediaManager::cached_thumbnail)
                 .unwrap_or_default(),
             img_fit,
             font,
             verse_font,
+            video_end: value.video_end,
+            muted: value.muted,
+            volume: value.volume,
+            transition: value.transition,
+            transition_duration: value.transition_ms,
+            missing: value.is_missing(),
+            category: value.category.to_shared_string(),
+            tags: value.tags.join(", ").to_shared_string(),
+            images: ModelRc::from(images.as_slice()),
+            image_seconds: value.image_seconds,
         }
     }
 }
@@ -200,7 +331,9 @@ impl From<ViewData> for MediaItem {
         let verse_font_stroke_color = value.verse_font.stroke;
 
         Self {
+            id: value.id,
             tmp: value.tmp,
+            watched: value.watched,
             is_logo: value.is_logo,
             path: (!value.path.is_empty()).then_some(value.path.to_string()),
             color: ViewBackgroundColor {
@@ -244,12 +377,46 @@ impl From<ViewData> for MediaItem {
                 stroke_size: value.verse_font.stroke_size,
                 font_size: value.verse_font.font_size,
             },
+            video_end: value.video_end,
+            muted: value.muted,
+            volume: value.volume,
+            transition: value.transition,
+            transition_ms: value.transition_duration,
+            // recorded when the item is stored in the library, see `relink::record_sizes`
+            size: None,
+            category: value.category.trim().to_string(),
+            tags: value
+                .tags
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect(),
+            // the pages of a document are rendered again when it is loaded
+            images: if document::is_document(&value.path) {
+                Vec::new()
+            } else {
+                value.images.iter().map(|i| i.to_string()).collect()
+            },
+            image_seconds: value.image_seconds,
         }
     }
 }
 
+/// Thumbnails and document pages are looked up while the schedule restores, before the
+/// manager exists.
+pub fn set_cache_dirs(data: &UserData) {
+    thumbnail::set_cache_dir(data.data_dir(&["cache", "thumbs"]));
+    document::set_cache_dir(data.data_dir(&["cache", "pages"]));
+}
+
 impl MediaManager {
-    pub fn new(window: &MainWindow, view_window: &ViewWindow, data: Arc<UserData>) -> Self {
+    pub fn new(
+        window: &MainWindow,
+        view_window: &ViewWindow,
+        schedule: ScheduleManager,
+        data: Arc<UserData>,
+    ) -> Self {
         let media_list = Arc::new(Mutex::new(data.load::<SourceMedia>()));
 
         let preview_video_playing = Arc::new(AtomicBool::new(false));
@@ -270,10 +437,16 @@ impl MediaManager {
         );
 
         let current_playbin: Arc<Mutex<Option<gst::Element>>> = Arc::new(Mutex::new(None));
+        let output_end = Rc::new(Cell::new(VideoEnd::Loop));
+        let resume_at = Rc::new(Cell::new(None));
 
         {
             let pipeline = pipeline.clone();
             let playbin_ref = current_playbin.clone();
+            let window = window.as_weak();
+            let output_video_playing = output_video_playing.clone();
+            let output_end = output_end.clone();
+            let resume_at = resume_at.clone();
 
             slint::spawn_local(async move {
                 while let Some(msg) = bus_receiver.next().await {
@@ -283,6 +456,16 @@ impl MediaManager {
                                 // keep GUI in sync if necessary
                             }
                         }
+                        MessageView::AsyncDone(_) => {
+                            if let Some(position) = resume_at.take() {
+                                _ = pipeline
+                                    .seek_simple(
+                                        gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT,
+                                        position,
+                                    )
+                                    .inspect_err(|e| error!("Cannot resume output video: {e}"));
+                            }
+                        }
                         MessageView::Error(err) => {
                             tracing::error!(
                                 "Error from {:?}: {} ({:?})",
@@ -292,6 +475,34 @@ impl MediaManager {
                             );
                         }
                         MessageView::Eos(_) => {
+                            // a looping schedule moves on instead of replaying the video
+                            if let Some(window) = window.upgrade() {
+                                let schedule = window.global::<ScheduleState>();
+                                if output_video_playing.load(Ordering::Relaxed)
+                                    && schedule.get_looping()
+                                    && schedule.get_live_advance() == 0
+                                {
+                                    window.invoke_auto_advance();
+                                    continue;
+                                }
+
+                                if output_video_playing.load(Ordering::Relaxed) {
+                                    match output_end.get() {
+                                        VideoEnd::Loop => {}
+                                        VideoEnd::Stop => {
+                                            window.invoke_end_output_media();
+                                            continue;
+                                        }
+                                        VideoEnd::Hold => {
+                                            // the sink keeps showing the last frame while paused
+                                            _ = pipeline.set_state(gst::State::Paused);
+                                            window.global::<ViewState>().set_output_paused(true);
+                                            continue;
+                                        }
+                                    }
+                                }
+                            }
+
                             // loop: seek pipeline to start
                             let _ = pipeline
                                 .seek_simple(
@@ -320,7 +531,12 @@ impl MediaManager {
             .unwrap();
         }
 
+        let watcher = Mutex::new(library::watcher(window.as_weak(), media_list.clone()));
+
         Self {
+            schedule,
+            media_folders: Mutex::new(data.load()),
+            watcher,
             data,
             media_list,
             output_video_playing,
@@ -330,24 +546,71 @@ impl MediaManager {
             pipeline,
             sink_element,
             current_playbin,
+            audio_device: RefCell::new(None),
+            output_end,
+            output_audio: Cell::new((false, 1.0)),
+            output_media: RefCell::new(None),
+            resume_at,
+            blanked: RefCell::new(None),
+            active_logo: RefCell::new(None),
+            slideshow: Rc::new(slint::Timer::default()),
+            volume_save: slint::Timer::default(),
             preview_enabled,
             output_enabled,
         }
     }
 
     pub fn initialize(&self) {
+        self.watch_media_folders();
         let width = self.window.unwrap().window().size().width;
         {
             let mut media_list = self.media_list.lock().unwrap();
             media_list.retain(|m| !m.tmp);
+            let assigned = MediaItem::assign_ids(&mut media_list);
+            if relink::record_sizes(&mut media_list) || assigned {
+                Self::save_permanent_items(&self.data, &media_list);
+            }
             set_media_list(width, self.window.clone(), media_list.clone());
         }
+        self.initialize_branding();
+        self.set_audio_devices();
+
+        if let Some(window) = self.window.upgrade() {
+            window
+                .global::<Settings>()
+                .set_fade_duration(self.fade_ms());
+        }
+    }
+
+    fn set_audio_devices(&self) {
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+        let settings = self.data.load::<AppSettings>();
+        let devices = audio_devices();
+        *self.audio_device.borrow_mut() = settings
+            .audio_device
+            .as_deref()
+            .and_then(|name| find_audio_device(&devices, name));
+
+        let names = std::iter::once("Default".to_shared_string())
+            .chain(devices.iter().map(|d| d.display_name().to_shared_string()))
+            .collect::<Vec<_>>();
+        let selected = settings
+            .audio_device
+            .and_then(|name| names.iter().position(|n| n.as_str() == name))
+            .unwrap_or_default();
+
+        let state = window.global::<Settings>();
+        state.set_audio_devices(ModelRc::from(names.as_slice()));
+        state.set_selected_audio_device(selected as i32);
+        state.set_master_volume(settings.master_volume.unwrap_or(1.0));
     }
 
     fn save_permanent_items(data: &Arc<UserData>, settings: &SourceMedia) {
         let permanent_items: SourceMedia = settings
             .iter()
-            .filter(|item| !item.tmp)
+            .filter(|item| !item.tmp && !item.watched)
             .cloned()
             .collect::<Vec<_>>()
             .into();
@@ -362,19 +625,12 @@ impl MediaManager {
         });
     }
 
-    fn unmark_all_logos(settings: &mut SourceMedia) {
-        for item in settings.iter_mut() {
-            item.is_logo = false;
-        }
-    }
-
-    pub fn connect_callbacks(self: Arc<Self>) {
+    pub fn connect_callbacks(self: Rc<Self>) {
         let window = self.window.unwrap();
         let state = window.global::<ViewState>();
 
         window.window().on_winit_window_event({
             let window = self.window.clone();
-            let media_list = self.media_list.clone();
             let resize_timer = Arc::new(Mutex::new(None::<JoinHandle<()>>));
             let last_resize = Arc::new(Mutex::new(Instant::now()));
             move |_, e| {
@@ -387,7 +643,6 @@ impl MediaManager {
                     }
 
                     let window = window.clone();
-                    let media_list = media_list.clone();
                     let last_resize = last_resize.clone();
                     let width = size.width;
 
@@ -397,8 +652,11 @@ impl MediaManager {
 
                         let elapsed = last_resize.lock().unwrap().elapsed();
                         if elapsed >= t {
-                            let media_list = media_list.lock().unwrap();
-                            set_media_list(width, window, media_list.clone());
+                            _ = slint::invoke_from_event_loop(move || {
+                                if let Some(window) = window.upgrade() {
+                                    show_tiles(&window.global::<ViewState>(), columns(width));
+                                }
+                            });
                         }
                     });
 
@@ -409,19 +667,9 @@ impl MediaManager {
             }
         });
 
-        state.on_show_logo({
+        state.on_fade_to({
             let instance = self.clone();
-            move || {
-                let settings = instance.media_list.lock().unwrap();
-
-                if let Some(logo) = settings.iter().find(|item| item.is_logo) {
-                    let logo_data = ViewData::from(logo);
-
-                    instance.play_output_video(logo_data);
-                } else {
-                    error!("No logo configured");
-                }
-            }
+            move |kind| instance.fade_to(kind)
         });
 
         state.on_preview_media({
@@ -438,11 +686,131 @@ impl MediaManager {
             }
         });
 
+        let settings_state = window.global::<Settings>();
+
+        settings_state.on_change_audio_device({
+            let instance = self.clone();
+            move |index| {
+                let Some(window) = instance.window.upgrade() else {
+                    return;
+                };
+                let state = window.global::<Settings>();
+                let mut settings = instance.data.load::<AppSettings>();
+                settings.audio_device = (index > 0)
+                    .then(|| state.get_audio_devices().row_data(index as usize))
+                    .flatten()
+                    .map(|name| name.to_string());
+                *instance.audio_device.borrow_mut() = settings
+                    .audio_device
+                    .as_deref()
+                    .and_then(|name| find_audio_device(&audio_devices(), name));
+                instance.data.save(&settings);
+                state.set_selected_audio_device(index);
+            }
+        });
+
+        settings_state.on_change_master_volume({
+            let instance = self.clone();
+            move |volume| {
+                let volume = volume.clamp(0.0, 1.0);
+                if let Some(window) = instance.window.upgrade() {
+                    window.global::<Settings>().set_master_volume(volume);
+                }
+                instance.apply_output_audio();
+
+                let data = instance.data.clone();
+                instance.volume_save.start(
+                    TimerMode::SingleShot,
+                    Duration::from_millis(500),
+                    move || {
+                        let mut settings = data.load::<AppSettings>();
+                        settings.master_volume = Some(volume);
+                        data.save(&settings);
+                    },
+                );
+            }
+        });
+
+        settings_state.on_change_fade_duration({
+            let instance = self.clone();
+            move |ms| {
+                let mut settings = instance.data.load::<AppSettings>();
+                settings.fade_ms = Some(ms.max(0));
+                instance.data.save(&settings);
+                if let Some(window) = instance.window.upgrade() {
+                    window.global::<Settings>().set_fade_duration(ms.max(0));
+                }
+            }
+        });
+
+        settings_state.on_change_logo({
+            let instance = self.clone();
+            move |index| instance.change_logo(index.max(0) as usize)
+        });
+
+        settings_state.on_apply_branding({
+            let instance = self.clone();
+            move |index| instance.apply_branding(index.max(0) as usize)
+        });
+
+        settings_state.on_save_branding({
+            let instance = self.clone();
+            move |name| instance.save_branding(&name)
+        });
+
+        settings_state.on_remove_branding({
+            let instance = self.clone();
+            move |index| instance.remove_branding(index.max(0) as usize)
+        });
+
+        state.on_toggle_pause({
+            let instance = self.clone();
+            move || instance.toggle_pause()
+        });
+
+        state.on_restart_video({
+            let instance = self.clone();
+            move || {
+                instance.seek_video(gst::ClockTime::ZERO);
+                if instance.is_paused() {
+                    instance.toggle_pause();
+                }
+            }
+        });
+
+        state.on_seek_video({
+            let instance = self.clone();
+            move |seconds| instance.seek_video(gst::ClockTime::from_seconds(seconds.max(0) as u64))
+        });
+
+        state.on_poll_position({
+            let instance = self.clone();
+            move || instance.poll_position()
+        });
+
+        state.on_show_page({
+            let instance = self.clone();
+            move |media_data, page| instance.show_page(media_data, page.max(0) as usize)
+        });
+
+        state.on_document_rendered({
+            let instance = self.clone();
+            move |path| {
+                if let Some(window) = instance.window.upgrade() {
+                    set_document_pages(&window, &path);
+                }
+                instance.schedule.set_document_pages(&path);
+            }
+        });
+
         state.on_select_file({
             let window = self.window.clone();
             move || {
                 let path = FileDialog::new()
-                    .add_filter("Media", ALL_MEDIA_FORMATS)
+                    .add_filter(
+                        "Media",
+                        &[ALL_MEDIA_FORMATS, document::DOCUMENT_FORMATS].concat(),
+                    )
                     .pick_file();
 
                 if let Some(path) = path {
@@ -460,20 +828,23 @@ impl MediaManager {
                             img_fit: i_slint_core::items::ImageFit::Contain,
                             font: default_preview.font,
                             verse_font: default_preview.verse_font,
+                            volume: 1.0,
                             ..ViewData::default()
                         };
 
-                        let is_img = path
-                            .extension()
-                            .and_then(|e| e.to_str())
-                            .map(|e| ALL_MEDIA_FORMATS.contains(&e))
-                            .unwrap_or_default();
+                        let is_img = has_extension(&path, ALL_MEDIA_FORMATS);
 
-                        if is_img {
+                        if document::is_document(&path_str) {
+                            // shown once its pages are rendered
+                            shared.path = path_str.to_shared_string();
+                            document::select_in_background(window.as_weak(), path);
+                        } else if is_img {
                             shared.path = path_str.to_shared_string();
                             shared.show_img = true;
-                            shared.img_bg =
-                                Self::generate_video_thumbnail(&path_str).unwrap_or_default();
+                            match Self::cached_thumbnail(&path_str) {
+                                Some(img) => shared.img_bg = img,
+                                None => select_thumbnail_in_background(window.as_weak(), path),
+                            }
                         } else {
                             shared.show_img = false;
                         }
@@ -484,7 +855,37 @@ impl MediaManager {
             }
         });
 
+        state.on_select_images({
+            let instance = self.clone();
+            move || instance.select_images()
+        });
+
+        state.on_filter_media({
+            let window = self.window.clone();
+            move || {
+                let window = window.unwrap();
+                let cols = columns(window.window().size().width);
+                show_tiles(&window.global::<ViewState>(), cols);
+            }
+        });
+
+        state.on_relink_media({
+            let instance = self.clone();
+            move || instance.relink_media()
+        });
+
+        state.on_add_media_folder({
+            let instance = self.clone();
+            move || instance.add_media_folder()
+        });
+
+        state.on_remove_media_folder({
+            let instance = self.clone();
+            move |index| instance.remove_media_folder(index.max(0) as usize)
+        });
+
         state.on_apply_changes({
+            let instance = self.clone();
             let data = self.data.clone();
             let main_window = self.window.clone();
             let media_list = self.media_list.clone();
@@ -496,56 +897,48 @@ impl MediaManager {
                 let preview = state.get_select_media_preview();
 
                 let mut new_item = MediaItem::from(preview);
+                // an edited watched file is kept with its changes from now on
+                new_item.watched = false;
+                // several logos can be kept, the branding presets pick between them
                 if new_item.is_logo {
-                    Self::unmark_all_logos(&mut settings);
                     new_item.tmp = false;
                 }
 
-                if edit_mode.editable {
-                    let cols = if let a @ 1.. = ((width as f32 * 0.7) / 250.).floor() as usize {
-                        a
-                    } else {
-                        6
-                    };
-
-                    let real_index = (edit_mode.row as usize * cols) + edit_mode.col as usize;
-
-                    if real_index < settings.len() {
-                        settings[real_index] = new_item;
-                        Self::sort_media_list(&mut settings);
-                        Self::save_permanent_items(&data, &settings);
+                let existing = settings
+                    .iter()
+                    .position(|m| edit_mode.editable && m.id == edit_mode.id);
+                if let Some(index) = existing {
+                    new_item.id = edit_mode.id;
+                    if settings[index].path == new_item.path {
+                        new_item.size = settings[index].size;
                     }
+                    settings[index] = new_item;
                 } else {
+                    new_item.id = MediaItem::next_id(&settings);
                     settings.push(new_item);
-                    Self::sort_media_list(&mut settings);
-                    Self::save_permanent_items(&data, &settings);
                 }
+                relink::record_sizes(&mut settings);
+                Self::sort_media_list(&mut settings);
+                Self::save_permanent_items(&data, &settings);
+                instance.set_logos(&settings);
 
                 set_media_list(width, main_window.clone(), settings.clone());
             }
         });
 
         state.on_remove_media({
+            let instance = self.clone();
             let data = self.data.clone();
             let main_window = self.window.clone();
             let media_list = self.media_list.clone();
-            move |row, idx| {
-                let window = main_window.unwrap();
-                let width = window.window().size().width;
+            move |id| {
+                let width = main_window.unwrap().window().size().width;
                 let mut settings = media_list.lock().unwrap();
 
-                let cols = if let a @ 1.. = ((width as f32 * 0.7) / 250.).floor() as usize {
-                    a
-                } else {
-                    6
-                };
-
-                let real_index = (row as usize * cols) + idx as usize;
-
-                if real_index < settings.len() {
-                    settings.remove(real_index);
-                    Self::sort_media_list(&mut settings);
+                if let Some(index) = settings.iter().position(|m| m.id == id) {
+                    settings.remove(index);
                     Self::save_permanent_items(&data, &settings);
+                    instance.set_logos(&settings);
                 }
 
                 set_media_list(width, main_window.clone(), settings.clone());
@@ -566,8 +959,13 @@ impl MediaManager {
     }
 
     pub fn stop_output_video(&self) {
+        self.stop_slideshow();
+        self.resume_at.set(None);
         self.output_video_playing.store(false, Ordering::Relaxed);
         self.output_enabled.store(false, Ordering::Relaxed);
+        if let Some(window) = self.window.upgrade() {
+            window.global::<ViewState>().set_output_video(false);
+        }
 
         if !self.preview_enabled.load(Ordering::Relaxed)
             && !self.output_enabled.load(Ordering::Relaxed)
@@ -576,7 +974,70 @@ impl MediaManager {
         }
     }
 
-    fn set_playbin_uri(&self, uri: &str) {
+    fn is_paused(&self) -> bool {
+        self.pipeline.current_state() == gst::State::Paused
+    }
+
+    pub fn toggle_pause(&self) {
+        if !self.output_video_playing.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let paused = !self.is_paused();
+        let target = if paused {
+            gst::State::Paused
+        } else {
+            gst::State::Playing
+        };
+        if self.pipeline.set_state(target).is_err() {
+            error!("Cannot change output video to {target:?}");
+            return;
+        }
+
+        if let Some(window) = self.window.upgrade() {
+            window.global::<ViewState>().set_output_paused(paused);
+        }
+    }
+
+    pub fn seek_video(&self, position: gst::ClockTime) {
+        _ = self
+            .pipeline
+            .seek_simple(gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT, position)
+            .inspect_err(|e| error!("Cannot seek output video: {e}"));
+    }
+
+    fn poll_position(&self) {
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+        let state = window.global::<ViewState>();
+
+        if let Some(position) = self.pipeline.query_position::<gst::ClockTime>() {
+            state.set_output_position(position.seconds() as i32);
+        }
+        if let Some(duration) = self.pipeline.query_duration::<gst::ClockTime>() {
+            state.set_output_duration(duration.seconds() as i32);
+        }
+    }
+
+    fn apply_output_audio(&self) {
+        if !self.output_video_playing.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(playbin) = self.current_playbin.lock().unwrap().clone() else {
+            return;
+        };
+
+        let (muted, volume) = self.output_audio.get();
+        let master = self
+            .window
+            .upgrade()
+            .map_or(1.0, |w| w.global::<Settings>().get_master_volume());
+        playbin.set_property("mute", muted);
+        playbin.set_property("volume", (volume * master).clamp(0.0, 1.0) as f64);
+    }
+
+    fn set_playbin_uri(&self, uri: &str, with_audio: bool) {
         // Remove previous playbin if exists
         if let Some(prev) = self.current_playbin.lock().unwrap().take() {
             let _ = prev.set_state(gst::State::Null);
@@ -591,6 +1052,13 @@ impl MediaManager {
         playbin.set_property("uri", &uri);
         // set the video-sink directly on playbin to our glsink
         playbin.set_property("video-sink", &self.sink_element);
+        // a sink belongs to one playbin, so every playbin opens the device again
+        if let Some(device) = self.audio_device.borrow().as_ref().filter(|_| with_audio) {
+            match device.create_element(None) {
+                Ok(audio_sink) => playbin.set_property("audio-sink", &audio_sink),
+                Err(e) => error!("Cannot open audio device {}: {e}", device.display_name()),
+            }
+        }
 
         self.pipeline
             .add(&playbin)
@@ -604,6 +1072,16 @@ impl MediaManager {
     pub fn play_preview_video(&self, media_data: ViewData) {
         // Set preview flag
         self.stop_preview_video(); // ensure previous preview state cleared
+
+        // a document previews its first page, playbin cannot open it
+        if document::is_document(&media_data.path) {
+            if let Some(page) = media_data.images.row_data(0) {
+                let page = PathBuf::from(page.as_str());
+                Self::show_image(&page, media_data, Some(self.window.clone()), None);
+            }
+            return;
+        }
+
         self.preview_video_playing.store(true, Ordering::Relaxed);
         self.preview_enabled.store(true, Ordering::Relaxed);
 
@@ -614,43 +1092,118 @@ impl MediaManager {
             return;
         }
 
-        self.set_playbin_uri(&format!("file://{}", source_path.to_string_lossy()));
+        self.set_playbin_uri(&format!("file://{}", source_path.to_string_lossy()), false);
+        // the preview never plays sound in the room
+        if let Some(playbin) = self.current_playbin.lock().unwrap().as_ref() {
+            playbin.set_property("mute", true);
+        }
 
         let _ = self.pipeline.set_state(gst::State::Playing);
     }
 
+    /// Stops the output video once another background went live, so its frames do not
+    /// keep replacing the new one.
+    pub fn stop_replaced_video(&self) {
+        if !self.output_video_playing.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(view_window) = self.view_window.upgrade() else {
+            return;
+        };
+        let path = view_window.global::<ViewState>().get_background().path;
+        let replaced = self
+            .output_media
+            .borrow()
+            .as_ref()
+            .is_some_and(|media| media.path != path);
+        if replaced {
+            self.stop_output_video();
+        }
+    }
+
     pub fn play_output_video(&self, media_data: ViewData) {
+        if document::is_document(&media_data.path) {
+            self.show_page(media_data, 0);
+            return;
+        }
+
         self.stop_output_video();
+        *self.output_media.borrow_mut() = Some(media_data.clone());
+        if media_data.images.row_count() > 0 {
+            self.play_slideshow(media_data);
+            return;
+        }
+
         self.output_video_playing.store(true, Ordering::Relaxed);
         self.output_enabled.store(true, Ordering::Relaxed);
 
         let path = media_data.path.to_string();
         let source_path = PathBuf::from(&path);
 
-        if Self::show_image(
+        self.output_end.set(media_data.video_end);
+        self.output_audio.set((media_data.muted, media_data.volume));
+
+        let is_image = Self::show_image(
             &source_path,
-            media_data,
+            media_data.clone(),
             None,
             Some(self.view_window.clone()),
-        ) {
+        );
+        if let Some(window) = self.window.upgrade() {
+            let state = window.global::<ViewState>();
+            state.set_output_video(!is_image);
+            state.set_output_paused(false);
+            state.set_output_position(0);
+            state.set_output_duration(0);
+        }
+        if is_image {
             return;
         }
 
-        self.set_playbin_uri(&format!("file://{}", source_path.to_string_lossy()));
+        // the thumbnail stands in on the background layer until the first frame arrives
+        if let Some(view_window) = self.view_window.upgrade() {
+            let mut media_data = media_data;
+            media_data.show_img = true;
+            view_window
+                .global::<ViewState>()
+                .invoke_present_background(media_data);
+        }
+
+        self.set_playbin_uri(&format!("file://{}", source_path.to_string_lossy()), true);
+
+        self.apply_output_audio();
 
         let _ = self.pipeline.set_state(gst::State::Playing);
     }
 
+    /// Pages are stepped from the schedule, each one entering like a new image.
+    pub fn show_page(&self, media_data: ViewData, page: usize) {
+        let page_path = media_data
+            .images
+            .row_data(page)
+            .or_else(|| document_pages(&media_data.path)?.get(page).cloned());
+        let Some(page_path) = page_path else {
+            error!("Page {page} of {} was not rendered", media_data.path);
+            return;
+        };
+
+        self.stop_output_video();
+        *self.output_media.borrow_mut() = Some(media_data.clone());
+        Self::show_image(
+            &PathBuf::from(page_path.as_str()),
+            media_data,
+            None,
+            Some(self.view_window.clone()),
+        );
+    }
+
     fn show_image(
         source_path: &PathBuf,
         mut media_data: ViewData,
         target_window: Option<Weak<MainWindow>>,
         view_window: Option<Weak<ViewWindow>>,
     ) -> bool {
-        if source_path
-            .extension()
-            .is_some_and(|e| IMAGE_FORMATS.contains(&e.to_str().unwrap_or_default()))
-        {
+        if has_extension(source_path, IMAGE_FORMATS) {
             let Ok(image) = Image::load_from_path(source_path.as_path()) else {
                 return false;
             };
@@ -670,12 +1223,11 @@ impl MediaManager {
                 let Some(view_window) = window.upgrade() else {
                     return false;
                 };
-                let state = view_window.global::<ViewState>();
-                let media = state.get_shared_view();
                 media_data.img_bg = image.clone();
                 media_data.show_img = true;
-                media_data.content = media.content;
-                state.set_shared_view(media_data);
+                view_window
+                    .global::<ViewState>()
+                    .invoke_present_background(media_data);
             }
             return true;
         }
@@ -683,86 +1235,177 @@ impl MediaManager {
         false
     }
 
-    fn generate_video_thumbnail(source_path: &str) -> Option<Image> {
-        let source_path = PathBuf::from(source_path);
-        if source_path
-            .extension()
-            .is_some_and(|e| IMAGE_FORMATS.contains(&e.to_str().unwrap_or_default()))
-        {
-            return Image::load_from_path(&source_path).ok();
-        }
-        let src_file = File::open(source_path)
-            .inspect_err(|e| error!("Cannot open file: {e}"))
-            .ok()?;
-        let size = src_file
-            .metadata()
-            .inspect_err(|e| error!("Cannot get metadata: {e}"))
-            .ok()?
-            .len();
-        let reader = BufReader::new(src_file);
-        let mut mp4_reader = Mp4Reader::read_header(reader, size)
-            .inspect_err(|e| error!("Cannot read mp4 reader: {e}"))
-            .ok()?;
-
-        let (_, track) = mp4_reader
-            .tracks()
-            .iter()
-            .find(|(_, t)| t.media_type().ok() == Some(mp4::MediaType::H264))?;
-
-        let decoder_options = DecoderConfig::new().flush_after_decode(Flush::NoFlush);
-        let mut decoder = Decoder::with_api_config(OpenH264API::from_source(), decoder_options)
-            .inspect_err(|e| error!("Cannot decode: {e}"))
-            .ok()?;
-
-        let track_id = track.track_id();
-        let width = track.width() as usize;
-        let height = track.height() as usize;
-        let mut bitstream_converter = Mp4BitstreamConverter::for_mp4_track(track)
-            .inspect_err(|e| error!("Cannot convert mp4 bitstream: {e}"))
-            .ok()?;
-        let mut buffer = Vec::new();
-
-        let sidx = track.sample_count().checked_div(2).unwrap_or(1);
-
-        for i in sidx..=track.sample_count() {
-            let Some(sample) = mp4_reader.read_sample(track_id, i).ok().flatten() else {
-                continue;
-            };
-
-            bitstream_converter.convert_packet(&sample.bytes, &mut buffer);
-
-            if let Ok(Some(image)) = decoder.decode(&buffer) {
-                let mut pixels = SharedPixelBuffer::<Rgb8Pixel>::new(width as _, height as _);
-                image.write_rgb8(pixels.make_mut_bytes());
-                return Some(Image::from_rgb8(pixels));
-            }
+    /// Never decodes video, missing thumbnails are filled in by `set_media_list` once generated
+    fn cached_thumbnail(source_path: &str) -> Option<Image> {
+        let source_path = Path::new(source_path);
+        if is_image(source_path) {
+            return Image::load_from_path(source_path).ok();
         }
-
-        None
+        Image::load_from_path(&thumbnail::cached(source_path)?).ok()
     }
 }
 
-fn set_media_list(width: u32, window: Weak<MainWindow>, media_list: SourceMedia) {
-    let cols = if let a @ 1.. = ((width as f32 * 0.7) / 250.).floor() as usize {
+fn columns(width: u32) -> usize {
+    if let a @ 1.. = ((width as f32 * 0.7) / 250.).floor() as usize {
         a
     } else {
         6
-    };
+    }
+}
+
+fn set_media_list(width: u32, window: Weak<MainWindow>, media_list: SourceMedia) {
+    let cols = columns(width);
     slint::invoke_from_event_loop({
         let window = window.clone();
         let media_list = media_list.clone();
         move || {
             let window = window.unwrap();
             let state = window.global::<ViewState>();
-            let media_list = media_list
+            state.set_missing_media(media_list.iter().filter(|m| m.is_missing()).count() as i32);
+            state.set_media_categories(ModelRc::from(filter::categories(&media_list).as_slice()));
+
+            let tiles = media_list.iter().map(ViewData::from).collect::<Vec<_>>();
+            let (documents, missing): (Vec<_>, Vec<_>) = tiles
                 .iter()
-                .map(ViewData::from)
-                .collect::<Vec<_>>()
-                .chunks(cols)
-                .map(ModelRc::from)
-                .collect::<Vec<_>>();
-            state.set_media_list(ModelRc::from(media_list.as_slice()));
+                .filter(|item| item.images.row_count() == 0)
+                .map(|item| PathBuf::from(item.path.as_str()))
+                .filter(|p| p.is_file() && !is_image(p) && thumbnail::cached(p).is_none())
+                .partition(|p| document::is_document(&p.to_string_lossy()));
+            TILES.with_borrow_mut(|t| *t = tiles);
+            show_tiles(&state, cols);
+
+            let window = window.as_weak();
+            document::render_pages_in_background(window.clone(), documents);
+            thumbnail::generate_in_background(missing, move |path, file| {
+                _ = slint::invoke_from_event_loop({
+                    let window = window.clone();
+                    move || {
+                        if let Some(window) = window.upgrade() {
+                            set_thumbnail(&window, &path, &file);
+                        }
+                    }
+                });
+            });
         }
     })
     .unwrap();
 }
+
+/// Fills in the selected file's preview once its thumbnail is generated, unless another
+/// file was picked meanwhile.
+fn select_thumbnail_in_background(window: Weak<MainWindow>, path: PathBuf) {
+    thumbnail::generate_in_background(vec![path], move |path, file| {
+        _ = slint::invoke_from_event_loop({
+            let window = window.clone();
+            move || {
+                let Some(window) = window.upgrade() else {
+                    return;
+                };
+                let state = window.global::<ViewState>();
+                let mut preview = state.get_select_media_preview();
+                if Path::new(preview.path.as_str()) != path {
+                    return;
+                }
+                if let Ok(img) = Image::load_from_path(&file) {
+                    preview.img_bg = img;
+                    state.set_select_media_preview(preview);
+                }
+            }
+        });
+    });
+}
+
+/// Lays out the tiles matching the search box and the selected category, reusing the
+/// images already loaded by `set_media_list`.
+fn show_tiles(state: &ViewState, cols: usize) {
+    let rows = TILES.with_borrow(|tiles| {
+        filter::visible(
+            tiles,
+            &state.get_media_search(),
+            &state.get_media_category(),
+        )
+        .into_iter()
+        .map(|index| tiles[index].clone())
+        .collect::<Vec<_>>()
+        .chunks(cols)
+        .map(ModelRc::from)
+        .collect::<Vec<_>>()
+    });
+    state.set_media_list(ModelRc::from(rows.as_slice()));
+}
+
+fn set_thumbnail(window: &MainWindow, path: &Path, file: &Path) {
+    let Ok(image) = Image::load_from_path(file) else {
+        return;
+    };
+
+    TILES.with_borrow_mut(|tiles| {
+        for tile in tiles
+            .iter_mut()
+            .filter(|t| Path::new(t.path.as_str()) == path)
+        {
+            tile.img_bg = image.clone();
+        }
+    });
+
+    for row in window.global::<ViewState>().get_media_list().iter() {
+        for (col, mut item) in row.iter().enumerate() {
+            if Path::new(item.path.as_str()) == path {
+                item.img_bg = image.clone();
+                row.set_row_data(col, item);
+            }
+        }
+    }
+}
+
+/// Turns the library tiles of a document into its pages once they are rendered.
+fn set_document_pages(window: &MainWindow, path: &str) {
+    let Some(pages) = document_pages(path).filter(|p| !p.is_empty()) else {
+        return;
+    };
+    let img_bg = Image::load_from_path(Path::new(pages[0].as_str())).unwrap_or_default();
+    let with_pages = |mut item: ViewData| {
+        item.images = ModelRc::from(pages.as_slice());
+        item.show_img = true;
+        item.img_bg = img_bg.clone();
+        item
+    };
+
+    TILES.with_borrow_mut(|tiles| {
+        for tile in tiles.iter_mut().filter(|t| t.path.as_str() == path) {
+            *tile = with_pages(tile.clone());
+        }
+    });
+
+    for row in window.global::<ViewState>().get_media_list().iter() {
+        for (col, item) in row.iter().enumerate() {
+            if item.path.as_str() == path {
+                row.set_row_data(col, with_pages(item));
+            }
+        }
+    }
+}
+
+fn audio_devices() -> Vec<gst::Device> {
+    let monitor = gst::DeviceMonitor::new();
+    monitor.add_filter(Some("Audio/Sink"), None);
+    if monitor
+        .start()
+        .inspect_err(|e| error!("Cannot list audio devices: {e}"))
+        .is_err()
+    {
+        return Vec::new();
+    }
+
+    let devices = monitor.devices().into_iter().collect();
+    monitor.stop();
+    devices
+}
+
+fn find_audio_device(devices: &[gst::Device], name: &str) -> Option<gst::Device> {
+    devices.iter().find(|d| d.display_name() == name).cloned()
+}
+
+fn is_image(path: &Path) -> bool {
+    has_extension(path, IMAGE_FORMATS)
+}
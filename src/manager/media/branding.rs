@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use slint::{ComponentHandle, ModelRc, SharedString, ToSharedString};
+use ui::{Settings, ViewData, ViewState};
+
+use super::{MediaItem, MediaManager};
+use crate::settings::{AppSettings, BrandingPreset, BrandingPresets, SourceMedia};
+
+impl MediaManager {
+    pub(super) fn initialize_branding(&self) {
+        let presets = self.data.load::<BrandingPresets>();
+        let active = self.data.load::<AppSettings>().branding;
+        let index = active
+            .as_ref()
+            .and_then(|name| presets.iter().position(|p| &p.name == name));
+
+        *self.active_logo.borrow_mut() = index.and_then(|i| presets[i].logo);
+        self.set_branding_presets(&presets, index);
+        self.set_logos(&self.media_list.lock().unwrap());
+    }
+
+    /// The logo picked by the active preset or by hand, else the first one.
+    pub(super) fn active_logo<'a>(&self, media_list: &'a SourceMedia) -> Option<&'a MediaItem> {
+        let id = *self.active_logo.borrow();
+        let mut logos = media_list.iter().filter(|m| m.is_logo);
+        logos
+            .clone()
+            .find(|m| Some(m.id) == id)
+            .or_else(|| logos.next())
+    }
+
+    pub(super) fn apply_branding(&self, index: usize) {
+        let presets = self.data.load::<BrandingPresets>();
+        let Some(preset) = presets.get(index) else {
+            return;
+        };
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+
+        let state = window.global::<ViewState>();
+        let background = ViewData::from(&preset.background);
+        state.set_shared_view(ViewData {
+            path: background.path,
+            show_img: background.show_img,
+            color: background.color,
+            img_bg: background.img_bg,
+            img_fit: background.img_fit,
+            font: preset.font.clone(),
+            verse_font: preset.verse_font.clone(),
+            ..state.get_shared_view()
+        });
+
+        *self.active_logo.borrow_mut() = preset.logo;
+        self.set_logos(&self.media_list.lock().unwrap());
+
+        let mut settings = self.data.load::<AppSettings>();
+        settings.branding = Some(preset.name.clone());
+        self.data.save(&settings);
+        self.set_branding_presets(&presets, Some(index));
+    }
+
+    pub(super) fn save_branding(&self, name: &str) {
+        let name = name.trim();
+        let Some(window) = self.window.upgrade().filter(|_| !name.is_empty()) else {
+            return;
+        };
+
+        let shared = window.global::<ViewState>().get_shared_view();
+        let preset = BrandingPreset {
+            name: name.to_string(),
+            logo: *self.active_logo.borrow(),
+            // only the background is kept, a song or text on screen is not part of the look
+            background: MediaItem::from(ViewData {
+                path: if shared.show_img {
+                    shared.path.clone()
+                } else {
+                    SharedString::default()
+                },
+                show_img: shared.show_img,
+                color: shared.color.clone(),
+                img_fit: shared.img_fit,
+                ..ViewData::default()
+            }),
+            font: shared.font,
+            verse_font: shared.verse_font,
+        };
+
+        let mut presets = self.data.load::<BrandingPresets>();
+        let index = match presets.iter().position(|p| p.name == name) {
+            Some(index) => {
+                presets[index] = preset;
+                index
+            }
+            None => {
+                presets.push(preset);
+                presets.len() - 1
+            }
+        };
+        self.data.save(&presets);
+
+        let mut settings = self.data.load::<AppSettings>();
+        settings.branding = Some(name.to_string());
+        self.data.save(&settings);
+        self.set_branding_presets(&presets, Some(index));
+    }
+
+    pub(super) fn remove_branding(&self, index: usize) {
+        let mut presets = self.data.load::<BrandingPresets>();
+        if index >= presets.len() {
+            return;
+        }
+        let removed = presets.remove(index);
+        self.data.save(&presets);
+
+        let mut settings = self.data.load::<AppSettings>();
+        if settings.branding.as_ref() == Some(&removed.name) {
+            settings.branding = None;
+            self.data.save(&settings);
+        }
+        let active = settings
+            .branding
+            .and_then(|name| presets.iter().position(|p| p.name == name));
+        self.set_branding_presets(&presets, active);
+    }
+
+    /// Picks the logo by its place in the logo list, the active preset keeps the choice.
+    pub(super) fn change_logo(&self, index: usize) {
+        let media_list = self.media_list.lock().unwrap();
+        let Some(logo) = media_list.iter().filter(|m| m.is_logo).nth(index) else {
+            return;
+        };
+        *self.active_logo.borrow_mut() = Some(logo.id);
+
+        if let Some(name) = self.data.load::<AppSettings>().branding {
+            let mut presets = self.data.load::<BrandingPresets>();
+            if let Some(preset) = presets.iter_mut().find(|p| p.name == name) {
+                preset.logo = Some(logo.id);
+                self.data.save(&presets);
+            }
+        }
+        self.set_logos(&media_list);
+    }
+
+    pub(super) fn set_logos(&self, media_list: &SourceMedia) {
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+
+        let active = self.active_logo(media_list).map(|m| m.id);
+        let logos = media_list.iter().filter(|m| m.is_logo).collect::<Vec<_>>();
+        let names = logos.iter().map(|m| logo_name(m)).collect::<Vec<_>>();
+        let selected = logos.iter().position(|m| Some(m.id) == active);
+
+        let state = window.global::<Settings>();
+        state.set_logos(ModelRc::from(names.as_slice()));
+        state.set_selected_logo(selected.map_or(-1, |i| i as i32));
+    }
+
+    fn set_branding_presets(&self, presets: &[BrandingPreset], selected: Option<usize>) {
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+
+        let names = presets
+            .iter()
+            .map(|p| p.name.to_shared_string())
+            .collect::<Vec<_>>();
+        let state = window.global::<Settings>();
+        state.set_branding_presets(ModelRc::from(names.as_slice()));
+        state.set_selected_branding(selected.map_or(-1, |i| i as i32));
+    }
+}
+
+fn logo_name(logo: &MediaItem) -> SharedString {
+    logo.path
+        .as_deref()
+        .and_then(|p| Path::new(p).file_name())
+        .map(|n| n.to_string_lossy().to_shared_string())
+        .unwrap_or_else(|| format!("Logo {}", logo.id).to_shared_string())
+}
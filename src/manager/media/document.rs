@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use hayro::hayro_interpret::InterpreterSettings;
+use hayro::hayro_syntax::{LoadPdfError, Pdf};
+use hayro::vello_cpu::color::palette::css::WHITE;
+use hayro::{PixmapSettings, RenderCache, RenderSettings};
+use notify_rust::Notification;
+use slint::{ComponentHandle, Image, Model, ModelRc, SharedString, ToSharedString, Weak};
+use tracing::error;
+use ui::{MainWindow, ViewData, ViewState};
+
+use super::thumbnail;
+
+pub const DOCUMENT_FORMATS: &[&str] = &["pdf"];
+// longest side of a rendered page, in pixels
+const PAGE_SIZE: u32 = 1920;
+
+static CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
+static PENDING: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
+// documents that could not be rendered, by `file_key`, so they are not tried on every refresh
+static FAILED: Mutex<Option<HashSet<u64>>> = Mutex::new(None);
+
+pub fn set_cache_dir(dir: PathBuf) {
+    if !dir.exists() {
+        _ = std::fs::create_dir_all(&dir)
+            .inspect_err(|e| error!("Cannot create page cache {}: {e}", dir.display()));
+    }
+    _ = CACHE_DIR.set(dir);
+}
+
+pub fn is_document(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| DOCUMENT_FORMATS.contains(&e.to_lowercase().as_str()))
+}
+
+/// One slide per page when a document is added to the schedule.
+pub fn document_slides(view_data: &ViewData) -> Vec<SharedString> {
+    if !is_document(&view_data.path) {
+        return Vec::new();
+    }
+    (1..=view_data.images.row_count())
+        .map(|page| format!("Slide {page}").to_shared_string())
+        .collect()
+}
+
+/// The cached pages of a document, `None` for any other file. Only the document is
+/// stored, pages missing from the cache are left to `render_pages_in_background`.
+pub fn document_pages(path: &str) -> Option<Vec<SharedString>> {
+    if !is_document(path) {
+        return None;
+    }
+    let pages = cached_pages(Path::new(path)).unwrap_or_default();
+    Some(
+        pages
+            .iter()
+            .map(|p| p.to_string_lossy().to_shared_string())
+            .collect(),
+    )
+}
+
+/// Renders the pages of the documents on a worker thread, then tells the window through
+/// `ViewState.document-rendered`. Documents already queued or that failed before are
+/// skipped.
+pub fn render_pages_in_background(window: Weak<MainWindow>, paths: Vec<PathBuf>) {
+    let paths = {
+        let mut failed = FAILED.lock().unwrap();
+        let failed = failed.get_or_insert_default();
+        let mut pending = PENDING.lock().unwrap();
+        let pending = pending.get_or_insert_default();
+        paths
+            .into_iter()
+            .filter(|p| thumbnail::file_key(p).is_some_and(|key| !failed.contains(&key)))
+            .filter(|p| pending.insert(p.clone()))
+            .collect::<Vec<_>>()
+    };
+    if paths.is_empty() {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        for path in paths {
+            let rendered = render_pages(&path)
+                .inspect_err(|e| error!("Cannot render pages of {}: {e}", path.display()));
+            if let Some(pending) = PENDING.lock().unwrap().as_mut() {
+                pending.remove(&path);
+            }
+            if rendered.is_err() {
+                remember_failure(&path);
+                continue;
+            }
+
+            _ = slint::invoke_from_event_loop({
+                let window = window.clone();
+                move || {
+                    if let Some(window) = window.upgrade() {
+                        let path = path.to_string_lossy().to_shared_string();
+                        window.global::<ViewState>().invoke_document_rendered(path);
+                    }
+                }
+            });
+        }
+    });
+}
+
+fn remember_failure(path: &Path) {
+    if let Some(key) = thumbnail::file_key(path) {
+        FAILED.lock().unwrap().get_or_insert_default().insert(key);
+    }
+}
+
+/// Renders the pages on a worker thread, then turns the media being edited into the
+/// document if it is still the one selected. A document that cannot be rendered is
+/// reported to the user.
+pub fn select_in_background(window: Weak<MainWindow>, path: PathBuf) {
+    std::thread::spawn(move || {
+        let pages = match render_pages(&path) {
+            Ok(pages) => pages,
+            Err(e) => {
+                error!("Cannot render pages of {}: {e}", path.display());
+                remember_failure(&path);
+                _ = Notification::new()
+                    .summary("Cannot open the document")
+                    .body(&format!("{}: {e}", path.display()))
+                    .show()
+                    .inspect_err(|e| error!("{e}"));
+                return;
+            }
+        };
+
+        _ = slint::invoke_from_event_loop(move || {
+            let Some(window) = window.upgrade() else {
+                return;
+            };
+            let state = window.global::<ViewState>();
+            let mut preview = state.get_select_media_preview();
+            if Path::new(preview.path.as_str()) != path {
+                return;
+            }
+
+            let pages = pages
+                .iter()
+                .map(|p| p.to_string_lossy().to_shared_string())
+                .collect::<Vec<_>>();
+            preview.show_img = true;
+            preview.img_bg =
+                Image::load_from_path(Path::new(pages[0].as_str())).unwrap_or_default();
+            preview.images = ModelRc::from(pages.as_slice());
+            state.set_select_media_preview(preview);
+        });
+    });
+}
+
+/// Renders every page to a PNG in the cache, reusing an earlier render of the same file.
+pub fn render_pages(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let dir = page_dir(path)?;
+    if !dir.is_dir() {
+        // rendered aside first so an interrupted run is not taken as finished
+        let partial = dir.with_extension("partial");
+        _ = std::fs::remove_dir_all(&partial);
+        std::fs::create_dir_all(&partial)?;
+        render(path, &partial)?;
+        std::fs::rename(&partial, &dir)?;
+    }
+
+    let pages = read_pages(&dir)?;
+    if pages.is_empty() {
+        return Err(io::Error::other("the document has no pages"));
+    }
+    Ok(pages)
+}
+
+/// The pages of an earlier render, without rendering anything.
+fn cached_pages(path: &Path) -> Option<Vec<PathBuf>> {
+    let dir = page_dir(path).ok().filter(|d| d.is_dir())?;
+    read_pages(&dir).ok().filter(|p| !p.is_empty())
+}
+
+fn page_dir(path: &Path) -> io::Result<PathBuf> {
+    let key = thumbnail::file_key(path)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "the file does not exist"))?;
+    Ok(CACHE_DIR
+        .get()
+        .ok_or_else(|| io::Error::other("the page cache is not set"))?
+        .join(format!("{key:016x}")))
+}
+
+fn read_pages(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    // the page numbers are padded, so name order is page order
+    let mut pages = std::fs::read_dir(dir)?
+        .flatten()
+        .map(|e| e.path())
+        .collect::<Vec<_>>();
+    pages.sort();
+    Ok(pages)
+}
+
+fn render(path: &Path, dir: &Path) -> io::Result<()> {
+    let pdf = Pdf::new(std::fs::read(path)?).map_err(|e| {
+        io::Error::other(match e {
+            LoadPdfError::Decryption(_) => "the document is password protected",
+            LoadPdfError::Invalid => "the file is not a valid PDF",
+        })
+    })?;
+
+    let cache = RenderCache::new();
+    let settings = InterpreterSettings::default();
+    for (index, page) in pdf.pages().iter().enumerate() {
+        let (width, height) = page.render_dimensions();
+        let scale = PAGE_SIZE as f32 / width.max(height).max(1.0);
+        let pixmap = hayro::render(
+            page,
+            &cache,
+            &settings,
+            &RenderSettings::default(),
+            &PixmapSettings {
+                x_scale: scale,
+                y_scale: scale,
+                bg_color: WHITE,
+            },
+        );
+
+        // the background is opaque, so the premultiplied pixels are plain RGBA
+        image::save_buffer(
+            dir.join(format!("page-{:04}.png", index + 1)),
+            pixmap.data_as_u8_slice(),
+            pixmap.width().into(),
+            pixmap.height().into(),
+            image::ExtendedColorType::Rgba8,
+        )
+        .map_err(io::Error::other)?;
+    }
+    Ok(())
+}
@@ -68,10 +68,10 @@ pub fn init(
                         if output_enabled.load(std::sync::atomic::Ordering::Relaxed) {
                             let app = app_weak.unwrap();
                             let state = app.global::<ViewState>();
-                            let mut shared = state.get_shared_view();
-                            shared.img_bg = next_frame;
-                            shared.show_img = true;
-                            state.set_shared_view(shared);
+                            let mut background = state.get_background();
+                            background.img_bg = next_frame;
+                            background.show_img = true;
+                            state.set_background(background);
                         }
                     }
                 }
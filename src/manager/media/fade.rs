@@ -0,0 +1,212 @@
+use std::sync::atomic::Ordering;
+
+use gst::prelude::{ElementExt, ElementExtManual};
+use slint::{ComponentHandle, Image, SharedString};
+use tracing::error;
+use ui::{BackgroundLayer, Blank, OverlayLayer, TextLayer, ViewData, ViewState};
+
+use super::MediaManager;
+use crate::settings::AppSettings;
+
+pub const DEFAULT_FADE_MS: i32 = 500;
+
+/// What a fade action hid, so repeating it puts the output back as it was.
+pub struct Blanked {
+    kind: Blank,
+    text: TextLayer,
+    background: BackgroundLayer,
+    logo: Option<Logo>,
+}
+
+enum Logo {
+    // an image over every other layer, the video under it is paused meanwhile
+    Overlay {
+        resume: bool,
+    },
+    // a video on the background layer, replacing the media that is replayed afterwards
+    Video {
+        replaced: Option<(Box<ViewData>, Option<gst::ClockTime>)>,
+    },
+}
+
+impl MediaManager {
+    pub fn fade_ms(&self) -> i32 {
+        self.data
+            .load::<AppSettings>()
+            .fade_ms
+            .unwrap_or(DEFAULT_FADE_MS)
+    }
+
+    pub fn fade_to(&self, kind: Blank) {
+        let current = self.blanked.borrow_mut().take();
+        if let Some(current) = current {
+            let same = current.kind == kind;
+            self.restore(current);
+            if same {
+                return;
+            }
+        }
+
+        let Some(view_window) = self.view_window.upgrade() else {
+            return;
+        };
+        let state = view_window.global::<ViewState>();
+        let fade = self.fade_ms();
+        let text = state.get_text();
+        let background = state.get_background();
+
+        let logo = match kind {
+            Blank::None => return,
+            Blank::Black => {
+                state.set_fade_length(fade as i64);
+                state.set_off(true);
+                None
+            }
+            Blank::Text => {
+                state.invoke_crossfade_text(without_text(&text), fade);
+                None
+            }
+            Blank::Logo => {
+                let Some(logo) = self.show_logo(&state, fade) else {
+                    return;
+                };
+                Some(logo)
+            }
+        };
+
+        *self.blanked.borrow_mut() = Some(Blanked {
+            kind,
+            text,
+            background,
+            logo,
+        });
+        self.set_blanked(kind);
+    }
+
+    /// Called once new text went live or the text layer was cleared, so the text hidden
+    /// before does not come back over it.
+    pub fn forget_hidden_text(&self) {
+        let mut blanked = self.blanked.borrow_mut();
+        match blanked.as_mut() {
+            Some(b) if b.kind == Blank::Text => {
+                *blanked = None;
+                drop(blanked);
+                self.set_blanked(Blank::None);
+            }
+            // the logo video gives back what is live now
+            Some(b) => {
+                if let Some(view_window) = self.view_window.upgrade() {
+                    b.text = view_window.global::<ViewState>().get_text();
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn show_logo(&self, state: &ViewState, fade: i32) -> Option<Logo> {
+        let logo = {
+            let media_list = self.media_list.lock().unwrap();
+            let Some(logo) = self.active_logo(&media_list) else {
+                error!("No logo configured");
+                return None;
+            };
+            ViewData::from(logo)
+        };
+
+        let video_playing = self.output_video_playing.load(Ordering::Relaxed);
+
+        // an image logo covers the layers below it, which are left untouched
+        if super::is_image(logo.path.as_str().as_ref()) {
+            let img = Image::load_from_path(logo.path.as_str().as_ref())
+                .inspect_err(|_| error!("Cannot load logo {}", logo.path))
+                .ok()?;
+
+            let resume = video_playing && !self.is_paused();
+            if resume {
+                _ = self.pipeline.set_state(gst::State::Paused);
+            }
+
+            state.set_fade_length(fade as i64);
+            state.set_overlay(OverlayLayer {
+                visible: true,
+                img,
+                img_fit: logo.img_fit,
+            });
+            return Some(Logo::Overlay { resume });
+        }
+
+        let replaced = self
+            .output_media
+            .borrow()
+            .clone()
+            .filter(|_| video_playing)
+            .map(|media| {
+                (
+                    Box::new(media),
+                    self.pipeline.query_position::<gst::ClockTime>(),
+                )
+            });
+
+        state.invoke_crossfade_text(without_text(&state.get_text()), fade);
+        self.play_output_video(logo);
+        Some(Logo::Video { replaced })
+    }
+
+    fn restore(&self, blanked: Blanked) {
+        self.set_blanked(Blank::None);
+        let Some(view_window) = self.view_window.upgrade() else {
+            return;
+        };
+        let state = view_window.global::<ViewState>();
+        let fade = self.fade_ms();
+
+        match blanked.kind {
+            Blank::None => {}
+            Blank::Black => {
+                state.set_fade_length(fade as i64);
+                state.set_off(false);
+            }
+            Blank::Text => state.invoke_crossfade_text(blanked.text, fade),
+            Blank::Logo => match blanked.logo {
+                Some(Logo::Overlay { resume }) => {
+                    state.set_fade_length(fade as i64);
+                    let mut overlay = state.get_overlay();
+                    overlay.visible = false;
+                    state.set_overlay(overlay);
+                    // the video may have been stopped while the logo was up
+                    if resume && self.output_video_playing.load(Ordering::Relaxed) {
+                        _ = self.pipeline.set_state(gst::State::Playing);
+                    }
+                }
+                Some(Logo::Video {
+                    replaced: Some((media, position)),
+                }) => {
+                    state.invoke_crossfade_text(blanked.text, fade);
+                    self.play_output_video(*media);
+                    // the new playbin only accepts the seek once it prerolled, see the bus
+                    self.resume_at.set(position);
+                }
+                Some(Logo::Video { replaced: None }) => {
+                    self.stop_output_video();
+                    state.invoke_crossfade_background(blanked.background, fade);
+                    state.invoke_crossfade_text(blanked.text, fade);
+                }
+                None => {}
+            },
+        }
+    }
+
+    fn set_blanked(&self, kind: Blank) {
+        if let Some(window) = self.window.upgrade() {
+            window.global::<ViewState>().set_blanked(kind);
+        }
+    }
+}
+
+fn without_text(text: &TextLayer) -> TextLayer {
+    TextLayer {
+        content: SharedString::default(),
+        verse: SharedString::default(),
+        ..text.clone()
+    }
+}
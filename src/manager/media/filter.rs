@@ -0,0 +1,95 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use slint::SharedString;
+use ui::ViewData;
+
+use super::MediaItem;
+
+/// Indices into `tiles` of the ones matching the search text and the selected category.
+pub fn visible(tiles: &[ViewData], search: &str, category: &str) -> Vec<usize> {
+    let search = search.trim().to_lowercase();
+
+    tiles
+        .iter()
+        .enumerate()
+        .filter(|(_, tile)| category.is_empty() || tile.category == category)
+        .filter(|(_, tile)| matches(tile, &search))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Matches the file name, the category or any tag.
+fn matches(tile: &ViewData, search: &str) -> bool {
+    if search.is_empty() {
+        return true;
+    }
+
+    let name = Path::new(tile.path.as_str())
+        .file_stem()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    name.contains(search)
+        || tile.category.to_lowercase().contains(search)
+        || tile
+            .tags
+            .split(',')
+            .any(|t| t.trim().to_lowercase().contains(search))
+}
+
+/// Every category in use, sorted, for the filter chips.
+pub fn categories(media_list: &[MediaItem]) -> Vec<SharedString> {
+    media_list
+        .iter()
+        .map(|m| m.category.as_str())
+        .filter(|c| !c.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(SharedString::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(path: &str, category: &str, tags: &str) -> ViewData {
+        ViewData {
+            path: SharedString::from(path),
+            category: SharedString::from(category),
+            tags: SharedString::from(tags),
+            ..Default::default()
+        }
+    }
+
+    fn tiles() -> Vec<ViewData> {
+        vec![
+            tile("/media/Sunrise.MP4", "Fondos", "amanecer, cálido"),
+            tile("/media/cross.png", "Logos", ""),
+            tile("/media/waves.mov", "Fondos", "mar"),
+        ]
+    }
+
+    #[test]
+    fn everything_is_visible_without_filters() {
+        assert_eq!(visible(&tiles(), "", ""), [0, 1, 2]);
+        assert_eq!(visible(&tiles(), "   ", ""), [0, 1, 2]);
+    }
+
+    #[test]
+    fn search_ignores_case_and_matches_names_categories_and_tags() {
+        assert_eq!(visible(&tiles(), "SUNRISE", ""), [0]);
+        assert_eq!(visible(&tiles(), "logo", ""), [1]);
+        assert_eq!(visible(&tiles(), " Mar ", ""), [2]);
+        // the extension is not part of the name
+        assert!(visible(&tiles(), "mp4", "").is_empty());
+    }
+
+    #[test]
+    fn category_narrows_the_search() {
+        assert_eq!(visible(&tiles(), "", "Fondos"), [0, 2]);
+        assert_eq!(visible(&tiles(), "cálido", "Fondos"), [0]);
+        assert!(visible(&tiles(), "cross", "Fondos").is_empty());
+    }
+}
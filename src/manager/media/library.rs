@@ -0,0 +1,205 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rfd::FileDialog;
+use slint::{ComponentHandle, ModelRc, ToSharedString, Weak};
+use tracing::error;
+use ui::{FileItem, MainWindow, VideoEnd, ViewState};
+
+use super::{
+    ALL_MEDIA_FORMATS, FontData, ImageFit, MediaItem, MediaManager, ViewBackgroundColor,
+    has_extension,
+};
+use crate::settings::SourceMedia;
+
+/// Keeps the media list in sync with the files inside the watched folders.
+pub fn watcher(
+    window: Weak<MainWindow>,
+    media_list: Arc<Mutex<SourceMedia>>,
+) -> RecommendedWatcher {
+    notify::recommended_watcher(move |res: notify::Result<Event>| {
+        let Ok(event) = res else {
+            return;
+        };
+        if !matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            return;
+        }
+
+        _ = slint::invoke_from_event_loop({
+            let window = window.clone();
+            let media_list = media_list.clone();
+            move || {
+                let mut media_list = media_list.lock().unwrap();
+                let mut changed = false;
+                for path in event.paths {
+                    if path.is_dir() {
+                        changed |= scan_folder(&path, &mut media_list);
+                    } else if path.is_file() {
+                        changed |= add_file(&path, &mut media_list);
+                    } else {
+                        changed |= remove_under(&path, &mut media_list);
+                    }
+                }
+
+                if let Some(main_window) = window.upgrade().filter(|_| changed) {
+                    let width = main_window.window().size().width;
+                    super::set_media_list(width, window, media_list.clone());
+                }
+            }
+        });
+    })
+    .unwrap()
+}
+
+impl MediaManager {
+    pub(super) fn watch_media_folders(&self) {
+        let folders = self.media_folders.lock().unwrap();
+        let mut watcher = self.watcher.lock().unwrap();
+        let mut media_list = self.media_list.lock().unwrap();
+
+        for folder in folders.iter() {
+            let path = Path::new(folder.path.as_str());
+            _ = watcher
+                .watch(path, RecursiveMode::Recursive)
+                .inspect_err(|e| error!("Cannot watch media folder {}: {e}", path.display()));
+            scan_folder(path, &mut media_list);
+        }
+
+        self.set_media_folders(&folders);
+    }
+
+    pub(super) fn add_media_folder(&self) {
+        let Some(path) = FileDialog::new().pick_folder() else {
+            return;
+        };
+        let mut folders = self.media_folders.lock().unwrap();
+        if folders.iter().any(|f| Path::new(f.path.as_str()) == path) {
+            return;
+        }
+
+        folders.push(FileItem {
+            path: path.to_string_lossy().to_shared_string(),
+            is_folder: true,
+        });
+        self.data.save(&*folders);
+        self.set_media_folders(&folders);
+
+        _ = self
+            .watcher
+            .lock()
+            .unwrap()
+            .watch(&path, RecursiveMode::Recursive)
+            .inspect_err(|e| error!("Cannot watch media folder {}: {e}", path.display()));
+
+        let mut media_list = self.media_list.lock().unwrap();
+        scan_folder(&path, &mut media_list);
+        self.refresh_media_list(&media_list);
+    }
+
+    pub(super) fn remove_media_folder(&self, index: usize) {
+        let mut folders = self.media_folders.lock().unwrap();
+        if index >= folders.len() {
+            return;
+        }
+
+        let removed = folders.remove(index);
+        self.data.save(&*folders);
+        self.set_media_folders(&folders);
+
+        let path = Path::new(removed.path.as_str());
+        _ = self.watcher.lock().unwrap().unwatch(path);
+
+        let mut media_list = self.media_list.lock().unwrap();
+        remove_under(path, &mut media_list);
+        self.refresh_media_list(&media_list);
+    }
+
+    fn set_media_folders(&self, folders: &[FileItem]) {
+        if let Some(window) = self.window.upgrade() {
+            window
+                .global::<ViewState>()
+                .set_media_folders(ModelRc::from(folders));
+        }
+    }
+
+    pub(super) fn refresh_media_list(&self, media_list: &SourceMedia) {
+        if let Some(window) = self.window.upgrade() {
+            let width = window.window().size().width;
+            super::set_media_list(width, self.window.clone(), media_list.clone());
+        }
+    }
+}
+
+fn scan_folder(folder: &Path, media_list: &mut SourceMedia) -> bool {
+    let Ok(entries) = std::fs::read_dir(folder)
+        .inspect_err(|e| error!("Cannot read media folder {}: {e}", folder.display()))
+    else {
+        return false;
+    };
+
+    let mut changed = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            changed |= scan_folder(&path, media_list);
+        } else {
+            changed |= add_file(&path, media_list);
+        }
+    }
+    changed
+}
+
+/// Files already in the list, watched or added by hand, are left as they are.
+fn add_file(path: &Path, media_list: &mut SourceMedia) -> bool {
+    let is_media = has_extension(path, ALL_MEDIA_FORMATS);
+    let size = std::fs::metadata(path).ok().map(|m| m.len());
+    let path = path.to_string_lossy().into_owned();
+    if !is_media
+        || media_list
+            .iter()
+            .any(|m| m.path.as_deref() == Some(path.as_str()))
+    {
+        return false;
+    }
+
+    let black = [0, 0, 0, 255];
+    let id = MediaItem::next_id(media_list);
+    media_list.push(MediaItem {
+        id,
+        tmp: false,
+        watched: true,
+        is_logo: false,
+        path: Some(path),
+        color: ViewBackgroundColor { a: black, b: black },
+        fit: ImageFit::Contain,
+        font: FontData::default(),
+        verse_font: FontData::default(),
+        video_end: VideoEnd::Loop,
+        muted: false,
+        volume: 1.0,
+        transition: Default::default(),
+        transition_ms: 0,
+        size,
+        category: String::new(),
+        tags: Vec::new(),
+        images: Vec::new(),
+        image_seconds: 0,
+    });
+    true
+}
+
+/// Drops the watched items at or below `path`, items added by hand are kept.
+fn remove_under(path: &Path, media_list: &mut SourceMedia) -> bool {
+    let before = media_list.len();
+    media_list.retain(|m| {
+        !m.watched
+            || m.path
+                .as_deref()
+                .is_none_or(|p| !Path::new(p).starts_with(path))
+    });
+    media_list.len() != before
+}
@@ -0,0 +1,139 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use rfd::FileDialog;
+use tracing::{error, info};
+
+use super::{MediaItem, MediaManager};
+use crate::settings::{BrandingPresets, SourceMedia};
+
+impl MediaManager {
+    /// Looks for the missing files inside a chosen folder and points every stored
+    /// reference, including saved schedules and branding presets, at the copies found.
+    pub(super) fn relink_media(&self) {
+        let Some(folder) = FileDialog::new().pick_folder() else {
+            return;
+        };
+
+        let mut media_list = self.media_list.lock().unwrap();
+        let mut presets = self.data.load::<BrandingPresets>();
+        // only the main file has a recorded size, slideshow images are matched by name
+        let mut missing = HashMap::new();
+        let backgrounds = presets.iter().map(|p| &p.background);
+        for item in media_list.iter().chain(backgrounds) {
+            for path in item.files().filter(|p| !Path::new(p).exists()) {
+                let size = item.size.filter(|_| item.path.as_deref() == Some(path));
+                missing.entry(path).or_insert(size);
+            }
+        }
+        if missing.is_empty() {
+            return;
+        }
+
+        let names = missing
+            .keys()
+            .filter_map(|path| Path::new(path).file_name())
+            .map(|n| n.to_owned())
+            .collect::<HashSet<_>>();
+        let mut found = HashMap::new();
+        find_files(&folder, &names, &mut found);
+
+        let renames = missing
+            .iter()
+            .filter_map(|(path, size)| {
+                let candidates = found.get(Path::new(path).file_name()?)?;
+                let new_path = pick_candidate(candidates, *size)?;
+                Some((path.to_string(), new_path.to_string_lossy().into_owned()))
+            })
+            .collect::<HashMap<_, _>>();
+
+        info!(
+            "Relinked {} of {} missing media files from {}",
+            renames.len(),
+            missing.len(),
+            folder.display()
+        );
+        if renames.is_empty() {
+            return;
+        }
+
+        for item in media_list.iter_mut() {
+            relink_item(item, &renames);
+        }
+        record_sizes(&mut media_list);
+        Self::save_permanent_items(&self.data, &media_list);
+
+        let mut presets_changed = false;
+        for preset in presets.iter_mut() {
+            presets_changed |= relink_item(&mut preset.background, &renames);
+        }
+        if presets_changed {
+            self.data.save(&presets);
+        }
+
+        self.refresh_media_list(&media_list);
+        drop(media_list);
+
+        self.schedule.relink_media(&renames);
+    }
+}
+
+/// Points the item's files at their new location, returns whether any moved.
+fn relink_item(item: &mut MediaItem, renames: &HashMap<String, String>) -> bool {
+    let mut relinked = false;
+    for path in item.path.iter_mut().chain(item.images.iter_mut()) {
+        if let Some(new_path) = renames.get(path) {
+            *path = new_path.clone();
+            relinked = true;
+        }
+    }
+    relinked
+}
+
+/// Stores the size of files that do not have one yet, returns whether any was recorded.
+pub fn record_sizes(media_list: &mut SourceMedia) -> bool {
+    let mut recorded = false;
+    for item in media_list.iter_mut().filter(|m| m.size.is_none()) {
+        if let Some(size) = item_size(item) {
+            item.size = Some(size);
+            recorded = true;
+        }
+    }
+    recorded
+}
+
+fn item_size(item: &MediaItem) -> Option<u64> {
+    Some(std::fs::metadata(item.path.as_deref()?).ok()?.len())
+}
+
+fn find_files(
+    folder: &Path,
+    names: &HashSet<OsString>,
+    found: &mut HashMap<OsString, Vec<PathBuf>>,
+) {
+    let Ok(entries) = std::fs::read_dir(folder)
+        .inspect_err(|e| error!("Cannot read folder {}: {e}", folder.display()))
+    else {
+        return;
+    };
+
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.is_dir() {
+            find_files(&path, names, found);
+        } else if let Some(name) = path.file_name().filter(|n| names.contains(*n)) {
+            found.entry(name.to_owned()).or_default().push(path.clone());
+        }
+    }
+}
+
+/// Prefers the copy with the same size, without a known size the name must be unique.
+fn pick_candidate(candidates: &[PathBuf], size: Option<u64>) -> Option<&PathBuf> {
+    match size {
+        Some(size) => candidates
+            .iter()
+            .find(|p| std::fs::metadata(p).is_ok_and(|m| m.len() == size)),
+        None if candidates.len() == 1 => candidates.first(),
+        None => None,
+    }
+}
@@ -0,0 +1,131 @@
+use std::cell::Cell;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use rfd::FileDialog;
+use slint::{
+    ComponentHandle, Image, Model, ModelRc, SharedString, TimerMode, ToSharedString, Weak,
+};
+use tracing::error;
+use ui::{ScheduleState, VideoEnd, ViewData, ViewState, ViewWindow};
+
+use super::{IMAGE_FORMATS, MediaManager};
+
+pub const DEFAULT_IMAGE_SECONDS: i32 = 8;
+
+impl MediaManager {
+    /// Shows the images one after another on the background layer, each one entering with
+    /// the item's transition. The end of the list is handled like the end of a video.
+    pub(super) fn play_slideshow(&self, media_data: ViewData) {
+        let images = media_data.images.iter().collect::<Vec<_>>();
+        let seconds = match media_data.image_seconds {
+            s @ 1.. => s,
+            _ => DEFAULT_IMAGE_SECONDS,
+        };
+
+        if let Some(window) = self.window.upgrade() {
+            let state = window.global::<ViewState>();
+            state.set_output_slideshow(true);
+            state.set_output_paused(false);
+        }
+        show_slide(&self.view_window, &media_data, &images[0]);
+
+        let next = Cell::new(1);
+        let timer = Rc::downgrade(&self.slideshow);
+        let window = self.window.clone();
+        let view_window = self.view_window.clone();
+        self.slideshow.start(
+            TimerMode::Repeated,
+            Duration::from_secs(seconds as u64),
+            move || {
+                let index = next.get();
+                if let Some(path) = images.get(index) {
+                    next.set(index + 1);
+                    show_slide(&view_window, &media_data, path);
+                    return;
+                }
+
+                let Some(window) = window.upgrade() else {
+                    return;
+                };
+                // a looping schedule moves on instead of starting over
+                let schedule = window.global::<ScheduleState>();
+                if schedule.get_looping() && schedule.get_live_advance() == 0 {
+                    window.invoke_auto_advance();
+                    return;
+                }
+
+                match media_data.video_end {
+                    VideoEnd::Loop => {
+                        next.set(1);
+                        if images.len() > 1 {
+                            show_slide(&view_window, &media_data, &images[0]);
+                        }
+                    }
+                    VideoEnd::Stop => window.invoke_end_output_media(),
+                    VideoEnd::Hold => {
+                        if let Some(timer) = timer.upgrade() {
+                            timer.stop();
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    pub(super) fn stop_slideshow(&self) {
+        self.slideshow.stop();
+        if let Some(window) = self.window.upgrade() {
+            window.global::<ViewState>().set_output_slideshow(false);
+        }
+    }
+
+    /// Turns the media being edited into a slideshow of the chosen images, in name order.
+    pub(super) fn select_images(&self) {
+        let Some(mut paths) = FileDialog::new()
+            .add_filter("Images", IMAGE_FORMATS)
+            .pick_files()
+            .filter(|p| !p.is_empty())
+        else {
+            return;
+        };
+        paths.sort();
+
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+        let state = window.global::<ViewState>();
+        let images = paths
+            .iter()
+            .map(|p| p.to_string_lossy().to_shared_string())
+            .collect::<Vec<_>>();
+
+        let mut preview = state.get_select_media_preview();
+        preview.path = images[0].clone();
+        preview.show_img = true;
+        preview.img_bg = Image::load_from_path(&paths[0]).unwrap_or_default();
+        preview.images = ModelRc::from(images.as_slice());
+        state.set_select_media_preview(preview);
+    }
+}
+
+fn show_slide(view_window: &Weak<ViewWindow>, media_data: &ViewData, path: &SharedString) {
+    let Some(view_window) = view_window.upgrade() else {
+        return;
+    };
+    let Ok(img_bg) = Image::load_from_path(Path::new(path.as_str()))
+        .inspect_err(|_| error!("Cannot load slide {path}"))
+    else {
+        return;
+    };
+
+    view_window
+        .global::<ViewState>()
+        .invoke_present_background(ViewData {
+            path: path.clone(),
+            show_img: true,
+            img_bg,
+            ..media_data.clone()
+        });
+}
@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use gst::prelude::*;
+use gst_video::video_frame::VideoFrameExt;
+use siphasher::sip::SipHasher13;
+use slint::{Rgb8Pixel, SharedPixelBuffer};
+use tracing::error;
+
+const THUMBNAIL_TIMEOUT: Duration = Duration::from_secs(5);
+const THUMBNAIL_WIDTH: u32 = 320;
+
+static CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
+static PENDING: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
+// files that could not be decoded, by `file_key`, so they are not tried on every refresh
+static FAILED: Mutex<Option<HashSet<u64>>> = Mutex::new(None);
+
+pub fn set_cache_dir(dir: PathBuf) {
+    if !dir.exists() {
+        _ = std::fs::create_dir_all(&dir)
+            .inspect_err(|e| error!("Cannot create thumbnail cache {}: {e}", dir.display()));
+    }
+    _ = CACHE_DIR.set(dir);
+}
+
+fn cache_file(path: &Path) -> Option<PathBuf> {
+    Some(
+        CACHE_DIR
+            .get()?
+            .join(format!("{:016x}.png", file_key(path)?)),
+    )
+}
+
+/// Identifies a file by path, modification time and size, so a replaced file gets
+/// fresh cache entries. The hash is fixed, keys stay valid across builds.
+pub fn file_key(path: &Path) -> Option<u64> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default();
+
+    let mut hasher = SipHasher13::new();
+    hasher.write(path.as_os_str().as_encoded_bytes());
+    hasher.write_u64(modified.as_secs());
+    hasher.write_u32(modified.subsec_nanos());
+    hasher.write_u64(metadata.len());
+    Some(hasher.finish())
+}
+
+/// Returns the cached thumbnail file without generating it.
+pub fn cached(path: &Path) -> Option<PathBuf> {
+    cache_file(path).filter(|f| f.is_file())
+}
+
+/// Generates the thumbnail into the cache if missing, blocking for at most `THUMBNAIL_TIMEOUT`.
+pub fn generate(path: &Path) -> Option<PathBuf> {
+    let file = cache_file(path)?;
+    if file.is_file() {
+        return Some(file);
+    }
+
+    let pixels = snapshot(path)?;
+    image::save_buffer(
+        &file,
+        pixels.as_bytes(),
+        pixels.width(),
+        pixels.height(),
+        image::ExtendedColorType::Rgb8,
+    )
+    .inspect_err(|e| error!("Cannot save thumbnail {}: {e}", file.display()))
+    .ok()?;
+
+    Some(file)
+}
+
+/// Generates the missing thumbnails on a worker thread, calling `on_ready` with the source
+/// path and the cached file as each one finishes. Paths already queued or that failed
+/// before are skipped.
+pub fn generate_in_background(
+    paths: Vec<PathBuf>,
+    on_ready: impl Fn(PathBuf, PathBuf) + Send + 'static,
+) {
+    let paths = {
+        let mut failed = FAILED.lock().unwrap();
+        let failed = failed.get_or_insert_default();
+        let mut pending = PENDING.lock().unwrap();
+        let pending = pending.get_or_insert_default();
+        paths
+            .into_iter()
+            .filter(|p| file_key(p).is_some_and(|key| !failed.contains(&key)))
+            .filter(|p| pending.insert(p.clone()))
+            .collect::<Vec<_>>()
+    };
+    if paths.is_empty() {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        for path in paths {
+            let file = generate(&path);
+            if let Some(pending) = PENDING.lock().unwrap().as_mut() {
+                pending.remove(&path);
+            }
+            match file {
+                Some(file) => on_ready(path, file),
+                None => {
+                    if let Some(key) = file_key(&path) {
+                        FAILED.lock().unwrap().get_or_insert_default().insert(key);
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Decodes a single frame a third into the video. Gives up once `THUMBNAIL_TIMEOUT`
+/// elapses so a broken file cannot block the caller.
+pub fn snapshot(path: &Path) -> Option<SharedPixelBuffer<Rgb8Pixel>> {
+    let uri = gst::glib::filename_to_uri(path, None)
+        .inspect_err(|e| error!("Cannot build uri for {}: {e}", path.display()))
+        .ok()?;
+
+    let pipeline = gst::parse::launch(&format!(
+        "uridecodebin uri=\"{uri}\" ! videoconvert ! videoscale ! \
+         appsink name=sink sync=false caps=video/x-raw,format=RGB,width={THUMBNAIL_WIDTH},pixel-aspect-ratio=1/1"
+    ))
+    .inspect_err(|e| error!("Cannot build thumbnail pipeline: {e}"))
+    .ok()?
+    .downcast::<gst::Pipeline>()
+    .ok()?;
+
+    let frame = capture(&pipeline, Instant::now() + THUMBNAIL_TIMEOUT);
+    _ = pipeline.set_state(gst::State::Null);
+
+    if frame.is_none() {
+        error!("Cannot generate thumbnail for {}", path.display());
+    }
+    frame
+}
+
+fn capture(pipeline: &gst::Pipeline, deadline: Instant) -> Option<SharedPixelBuffer<Rgb8Pixel>> {
+    let sink = pipeline
+        .by_name("sink")?
+        .downcast::<gst_app::AppSink>()
+        .ok()?;
+
+    pipeline.set_state(gst::State::Paused).ok()?;
+    wait_preroll(pipeline, deadline)?;
+
+    if let Some(duration) = pipeline.query_duration::<gst::ClockTime>() {
+        pipeline
+            .seek_simple(
+                gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT,
+                duration / 3,
+            )
+            .ok()?;
+        wait_preroll(pipeline, deadline)?;
+    }
+
+    let sample = sink.try_pull_preroll(remaining(deadline)?)?;
+    let info = gst_video::VideoInfo::from_caps(sample.caps()?).ok()?;
+    let frame = gst_video::VideoFrame::from_buffer_readable(sample.buffer_owned()?, &info).ok()?;
+
+    let (width, height) = (frame.width(), frame.height());
+    let stride = frame.plane_stride()[0] as usize;
+    let data = frame.plane_data(0).ok()?;
+    let row = width as usize * 3;
+    if row == 0 {
+        return None;
+    }
+
+    let mut pixels = SharedPixelBuffer::<Rgb8Pixel>::new(width, height);
+    for (dst, src) in pixels
+        .make_mut_bytes()
+        .chunks_exact_mut(row)
+        .zip(data.chunks(stride))
+    {
+        dst.copy_from_slice(&src[..row]);
+    }
+
+    Some(pixels)
+}
+
+fn wait_preroll(pipeline: &gst::Pipeline, deadline: Instant) -> Option<()> {
+    let bus = pipeline.bus()?;
+    let message = bus.timed_pop_filtered(
+        remaining(deadline)?,
+        &[gst::MessageType::AsyncDone, gst::MessageType::Error],
+    )?;
+
+    match message.view() {
+        gst::MessageView::Error(err) => {
+            error!("Thumbnail pipeline error: {}", err.error());
+            None
+        }
+        _ => Some(()),
+    }
+}
+
+fn remaining(deadline: Instant) -> Option<gst::ClockTime> {
+    let left = deadline.checked_duration_since(Instant::now())?;
+    gst::ClockTime::try_from(left).ok()
+}
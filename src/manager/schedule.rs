@@ -1,35 +1,192 @@
-use slint::{ComponentHandle, ModelRc, SharedString, Weak};
+use rfd::FileDialog;
+use setup_core::TantivySink;
+use setup_core::service_db::SearchedVerse;
+use slint::{ComponentHandle, Image, Model, ModelRc, SharedString, ToSharedString, Weak};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::atomic::{AtomicI32, Ordering};
 use std::sync::{Arc, Mutex};
-use tracing::debug;
+use tracing::{debug, error};
 
-use ui::{MainWindow, ScheduleState, ScheduledItem, ScheduledKind, ViewData, ViewState};
+use ui::{MainWindow, ScheduleState, ScheduledItem, ScheduledKind, Settings, ViewData, ViewState};
 
-use super::SongsManager;
+use crate::settings::{AppSettings, ScheduleAutosave};
+use crate::user_data::UserData;
 
+use super::{
+    SongsManager, document_pages, document_slides, is_document, render_pages_in_background,
+};
+
+mod deck;
+mod export;
+mod file;
+mod history;
+mod navigation;
+mod package;
+mod plan;
+
+use deck::DECK_EXTENSIONS;
+use export::RUN_SHEET_EXTENSION;
+pub use file::ScheduleFile;
+use file::{SCHEDULE_EXTENSION, schedule_name};
+use history::History;
+use package::PACKAGE_EXTENSION;
+use plan::{PLAN_EXTENSIONS, PlanKind};
+
+const MAX_RECENT_SCHEDULES: usize = 10;
+
+#[derive(Clone)]
 pub struct ScheduleManager {
+    data: Arc<UserData>,
     window: Weak<MainWindow>,
     schedule_cache: Arc<Mutex<Vec<ScheduledItem>>>,
+    current_path: Arc<Mutex<Option<PathBuf>>>,
+    // only touched on the UI thread, its snapshots hold slint models
+    history: Rc<RefCell<History>>,
     id_counter: Arc<AtomicI32>,
     song_manager: Arc<SongsManager>,
+    database: Arc<TantivySink>,
 }
 
 impl ScheduleManager {
-    pub fn new(window: Weak<MainWindow>, song_manager: Arc<SongsManager>) -> Self {
+    pub fn new(
+        window: Weak<MainWindow>,
+        song_manager: Arc<SongsManager>,
+        database: Arc<TantivySink>,
+        data: Arc<UserData>,
+    ) -> Self {
         Self {
+            data,
             window,
             song_manager,
+            database,
             schedule_cache: Arc::new(Mutex::new(Vec::new())),
+            current_path: Arc::new(Mutex::new(None)),
+            history: Rc::new(RefCell::new(History::default())),
             id_counter: Arc::new(AtomicI32::new(1)),
         }
     }
 
+    /// Gives the items showing the document its pages once they are in the cache.
+    pub fn set_document_pages(&self, path: &str) {
+        let Some(pages) = document_pages(path).filter(|p| !p.is_empty()) else {
+            return;
+        };
+        let img_bg = Image::load_from_path(Path::new(pages[0].as_str())).unwrap_or_default();
+
+        let mut cache = self.schedule_cache.lock().unwrap();
+        let mut changed = false;
+        for item in cache
+            .iter_mut()
+            .filter(|i| i.view_data.path.as_str() == path)
+        {
+            item.view_data.images = ModelRc::from(pages.as_slice());
+            item.view_data.show_img = true;
+            item.view_data.img_bg = img_bg.clone();
+            changed = true;
+        }
+
+        if !changed {
+            return;
+        }
+        if let Some(window) = self.window.upgrade() {
+            window
+                .global::<ScheduleState>()
+                .set_items(ModelRc::from(cache.as_slice()));
+        }
+    }
+
+    /// Rewrites moved media paths in the open schedule and in every saved schedule and template.
+    pub fn relink_media(&self, renames: &HashMap<String, String>) {
+        {
+            let mut cache = self.schedule_cache.lock().unwrap();
+            let mut changed = false;
+            for item in cache.iter_mut() {
+                let view_data = &mut item.view_data;
+                let relink =
+                    |path: &SharedString| renames.get(path.as_str()).map(|p| p.to_shared_string());
+
+                let mut relinked = false;
+                if let Some(path) = relink(&view_data.path) {
+                    if let Some(pages) = document_pages(&path) {
+                        view_data.images = ModelRc::from(pages.as_slice());
+                    }
+                    view_data.path = path;
+                    relinked = true;
+                }
+                let images = view_data.images.iter().collect::<Vec<_>>();
+                if images.iter().any(|p| renames.contains_key(p.as_str())) {
+                    let images = images
+                        .iter()
+                        .map(|p| relink(p).unwrap_or_else(|| p.clone()))
+                        .collect::<Vec<_>>();
+                    view_data.images = ModelRc::from(images.as_slice());
+                    relinked = true;
+                }
+
+                if relinked {
+                    view_data.missing = std::iter::once(view_data.path.clone())
+                        .chain(view_data.images.iter())
+                        .filter(|p| !p.is_empty())
+                        .any(|p| !Path::new(p.as_str()).exists());
+                    changed = true;
+                }
+            }
+
+            if changed {
+                update_schedule(
+                    &self.window,
+                    &self.data,
+                    self.current_path.lock().unwrap().clone(),
+                    &self.history.borrow(),
+                    &cache,
+                );
+            }
+        }
+
+        let recent = recent_schedules(&self.data.load::<AppSettings>())
+            .into_iter()
+            .map(PathBuf::from);
+        let files = [schedules_dir(&self.data), templates_dir(&self.data)]
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|e| e == SCHEDULE_EXTENSION))
+            .chain(recent)
+            .collect::<HashSet<_>>();
+
+        for path in files {
+            let Some(mut schedule) = ScheduleFile::load(&path) else {
+                continue;
+            };
+            if schedule.relink(renames) {
+                schedule.save(&path);
+            }
+        }
+    }
+
     pub fn initialize(&self) {
+        let autosave = self.data.load::<ScheduleAutosave>();
+        let path = autosave.path.map(PathBuf::from);
+
+        let mut cache = self.schedule_cache.lock().unwrap();
+        *cache = self.resolve(autosave.schedule);
+        *self.current_path.lock().unwrap() = path.clone();
+
+        let next_id = cache.iter().map(|i| i.id).max().unwrap_or_default() + 1;
+        self.id_counter.store(next_id, Ordering::SeqCst);
+
         if let Some(window) = self.window.upgrade() {
             let state = window.global::<ScheduleState>();
-            let empty: Vec<ScheduledItem> = Vec::new();
-            state.set_items(ModelRc::from(empty.as_slice()));
+            state.set_items(ModelRc::from(cache.as_slice()));
+            state.set_name(schedule_name(path.as_deref()));
+            set_navigation(&state, &cache);
         }
+        render_missing_pages(&self.window, &cache);
+        set_recent_schedules(&self.window, &self.data.load::<AppSettings>());
+        set_templates(&self.window, &self.data);
     }
 
     pub fn connect_callbacks(&self) {
@@ -41,15 +198,21 @@ impl ScheduleManager {
         let cache = self.schedule_cache.clone();
         let window_weak = self.window.clone();
         let id_counter = self.id_counter.clone();
+        let data = self.data.clone();
+        let current_path = self.current_path.clone();
+        let history = self.history.clone();
 
         window.on_add_processed_item({
             let cache = cache.clone();
             let window_weak = window_weak.clone();
             let id_counter = id_counter.clone();
+            let data = data.clone();
+            let current_path = current_path.clone();
+            let history = history.clone();
             let song_manager = self.song_manager.clone();
             move |vd: ViewData, kind: ScheduledKind, label: SharedString| {
                 debug!(
-                    "ScheduleManager: received add-processed-item kind={kind:?} label={label} path={}",
+                    "ScheduleManager: add-processed-item kind={kind:?} label={label} path={}",
                     vd.path
                 );
                 let id = id_counter.fetch_add(1, Ordering::SeqCst);
@@ -60,47 +223,46 @@ impl ScheduleManager {
 
                 {
                     let mut guard = cache.lock().unwrap();
-                    if kind == ScheduledKind::Song {
+                    let mut history = history.borrow_mut();
+                    let path = current_path.lock().unwrap().clone();
+                    let item = if kind == ScheduledKind::Song {
                         let state = window.global::<ViewState>().get_shared_view();
-                        let Some(media) = song_manager
-                            .songs_cache
-                            .lock()
-                            .map(|media| {
-                                media.iter().find(|m| m.path.ends_with(label.as_str())).map(
-                                    move |m| {
-                                        m.content
-                                            .iter()
-                                            .map(|c| ScheduledItem {
-                                                id,
-                                                kind,
-                                                label: c.clone(),
-                                                view_data: ViewData {
-                                                    content: c.clone(),
-                                                    path: m.path.clone(),
-                                                    ..state.clone()
-                                                },
-                                            })
-                                            .collect::<Vec<_>>()
-                                    },
-                                )
-                            })
-                            .ok()
-                            .flatten()
+                        let songs = song_manager.songs_cache.lock().unwrap();
+                        let Some(m) = songs.iter().find(|m| m.path.ends_with(label.as_str()))
                         else {
                             return;
                         };
-                        guard.extend_from_slice(media.as_slice());
+                        ScheduledItem {
+                            id,
+                            kind,
+                            label: m.path.clone(),
+                            song: m.path.clone(),
+                            // a song has no reference, whatever the preview held before
+                            view_data: ViewData {
+                                content: m.content.first().cloned().unwrap_or_default(),
+                                verse: SharedString::default(),
+                                ..state
+                            },
+                            slides: ModelRc::from(m.content.as_slice()),
+                            notes: SharedString::default(),
+                            advance: 0,
+                        }
                     } else {
-                        guard.push(ScheduledItem {
+                        ScheduledItem {
                             id,
                             kind,
                             label: label.clone(),
+                            song: SharedString::default(),
+                            slides: ModelRc::from(document_slides(&vd).as_slice()),
                             view_data: vd,
-                        });
-                    }
+                            notes: SharedString::default(),
+                            advance: 0,
+                        }
+                    };
+                    history.record(&guard, path.as_deref());
+                    place_item(&window, &mut guard, item);
 
-                    let state = window.global::<ScheduleState>();
-                    state.set_items(ModelRc::from(guard.as_slice()));
+                    update_schedule(&window_weak, &data, path, &history, &guard);
                 }
             }
         });
@@ -108,15 +270,19 @@ impl ScheduleManager {
         window.on_schedule_request_remove({
             let cache = cache.clone();
             let window_weak = window_weak.clone();
+            let data = data.clone();
+            let current_path = current_path.clone();
+            let history = history.clone();
             move |index: i32| {
                 let mut guard = cache.lock().unwrap();
                 let idx = index as usize;
                 if idx < guard.len() {
+                    history
+                        .borrow_mut()
+                        .record(&guard, current_path.lock().unwrap().as_deref());
                     guard.remove(idx);
-                    if let Some(window) = window_weak.upgrade() {
-                        let state = window.global::<ScheduleState>();
-                        state.set_items(ModelRc::from(guard.as_slice()));
-                    }
+                    let path = current_path.lock().unwrap().clone();
+                    update_schedule(&window_weak, &data, path, &history.borrow(), &guard);
                 }
             }
         });
@@ -124,6 +290,9 @@ impl ScheduleManager {
         window.on_schedule_request_move_by({
             let cache = cache.clone();
             let window_weak = window_weak.clone();
+            let data = data.clone();
+            let current_path = current_path.clone();
+            let history = history.clone();
             move |start_index: i32, offset: i32| {
                 let mut guard = cache.lock().unwrap();
                 let len = guard.len();
@@ -146,6 +315,12 @@ impl ScheduleManager {
                 };
 
                 let s_usize = s as usize;
+                if dest == s_usize {
+                    return;
+                }
+                history
+                    .borrow_mut()
+                    .record(&guard, current_path.lock().unwrap().as_deref());
                 let item = guard.remove(s_usize);
 
                 if dest > guard.len() {
@@ -154,25 +329,894 @@ impl ScheduleManager {
 
                 guard.insert(dest, item);
 
-                if let Some(window) = window_weak.upgrade() {
-                    let state = window.global::<ScheduleState>();
-                    state.set_items(ModelRc::from(guard.as_slice()));
-                }
+                let path = current_path.lock().unwrap().clone();
+                update_schedule(&window_weak, &data, path, &history.borrow(), &guard);
             }
         });
 
         window.on_schedule_request_clear({
             let cache = cache.clone();
             let window_weak = window_weak.clone();
+            let data = data.clone();
+            let current_path = current_path.clone();
+            let history = history.clone();
             move || {
                 let mut guard = cache.lock().unwrap();
+                if guard.is_empty() {
+                    return;
+                }
+                history
+                    .borrow_mut()
+                    .record(&guard, current_path.lock().unwrap().as_deref());
                 guard.clear();
-                if let Some(window) = window_weak.upgrade() {
-                    let state = window.global::<ScheduleState>();
-                    let empty: Vec<ScheduledItem> = Vec::new();
-                    state.set_items(ModelRc::from(empty.as_slice()));
+                let path = current_path.lock().unwrap().clone();
+                update_schedule(&window_weak, &data, path, &history.borrow(), &guard);
+            }
+        });
+
+        let state = window.global::<ScheduleState>();
+
+        state.on_new_schedule({
+            let cache = cache.clone();
+            let window_weak = window_weak.clone();
+            let data = data.clone();
+            let current_path = current_path.clone();
+            let history = history.clone();
+            move || {
+                let mut guard = cache.lock().unwrap();
+                let mut current = current_path.lock().unwrap();
+                // already a new schedule, an undo step would only bring back the same
+                if guard.is_empty() && current.is_none() {
+                    return;
+                }
+                history.borrow_mut().record(&guard, current.as_deref());
+                *current = None;
+                drop(current);
+                guard.clear();
+                update_schedule(&window_weak, &data, None, &history.borrow(), &guard);
+            }
+        });
+
+        state.on_undo({
+            let instance = self.clone();
+            move || {
+                let mut guard = instance.schedule_cache.lock().unwrap();
+                let mut history = instance.history.borrow_mut();
+                let mut current_path = instance.current_path.lock().unwrap();
+                if let Some(snapshot) = history.undo(&guard, current_path.as_deref()) {
+                    *guard = snapshot.items;
+                    *current_path = snapshot.path.clone();
+                    update_schedule(
+                        &instance.window,
+                        &instance.data,
+                        snapshot.path,
+                        &history,
+                        &guard,
+                    );
+                }
+            }
+        });
+
+        state.on_redo({
+            let instance = self.clone();
+            move || {
+                let mut guard = instance.schedule_cache.lock().unwrap();
+                let mut history = instance.history.borrow_mut();
+                let mut current_path = instance.current_path.lock().unwrap();
+                if let Some(snapshot) = history.redo(&guard, current_path.as_deref()) {
+                    *guard = snapshot.items;
+                    *current_path = snapshot.path.clone();
+                    update_schedule(
+                        &instance.window,
+                        &instance.data,
+                        snapshot.path,
+                        &history,
+                        &guard,
+                    );
+                }
+            }
+        });
+
+        state.on_add_header({
+            let instance = self.clone();
+            move |label| {
+                let mut guard = instance.schedule_cache.lock().unwrap();
+                let mut history = instance.history.borrow_mut();
+                history.record(&guard, instance.current_path.lock().unwrap().as_deref());
+                guard.push(ScheduledItem {
+                    id: instance.id_counter.fetch_add(1, Ordering::SeqCst),
+                    kind: ScheduledKind::Header,
+                    label,
+                    song: SharedString::default(),
+                    view_data: ViewData::default(),
+                    slides: ModelRc::default(),
+                    notes: SharedString::default(),
+                    advance: 0,
+                });
+                let path = instance.current_path.lock().unwrap().clone();
+                update_schedule(&instance.window, &instance.data, path, &history, &guard);
+            }
+        });
+
+        state.on_add_placeholder({
+            let instance = self.clone();
+            move |label| {
+                let mut guard = instance.schedule_cache.lock().unwrap();
+                let mut history = instance.history.borrow_mut();
+                history.record(&guard, instance.current_path.lock().unwrap().as_deref());
+                guard.push(ScheduledItem {
+                    id: instance.id_counter.fetch_add(1, Ordering::SeqCst),
+                    kind: ScheduledKind::Placeholder,
+                    label,
+                    ..Default::default()
+                });
+                let path = instance.current_path.lock().unwrap().clone();
+                update_schedule(&instance.window, &instance.data, path, &history, &guard);
+            }
+        });
+
+        state.on_set_notes({
+            let instance = self.clone();
+            move |index, notes| {
+                let mut guard = instance.schedule_cache.lock().unwrap();
+                let Some(item) = guard.get(index as usize) else {
+                    return;
+                };
+                if item.notes == notes {
+                    return;
+                }
+
+                let mut history = instance.history.borrow_mut();
+                history.record(&guard, instance.current_path.lock().unwrap().as_deref());
+                guard[index as usize].notes = notes;
+                let path = instance.current_path.lock().unwrap().clone();
+                update_schedule(&instance.window, &instance.data, path, &history, &guard);
+            }
+        });
+
+        state.on_step({
+            let instance = self.clone();
+            move |offset| {
+                let Some(window) = instance.window.upgrade() else {
+                    return false;
+                };
+                let state = window.global::<ScheduleState>();
+                let guard = instance.schedule_cache.lock().unwrap();
+
+                let Some((item, slide)) = navigation::step(
+                    &guard,
+                    state.get_selected_item(),
+                    state.get_selected_slide(),
+                    offset,
+                ) else {
+                    return false;
+                };
+
+                state.set_selected_item(item);
+                state.set_selected_slide(slide);
+                set_navigation(&state, &guard);
+                true
+            }
+        });
+
+        state.on_loop_step({
+            let instance = self.clone();
+            move || {
+                let Some(window) = instance.window.upgrade() else {
+                    return false;
+                };
+                let state = window.global::<ScheduleState>();
+                let guard = instance.schedule_cache.lock().unwrap();
+
+                let Some((item, slide)) = navigation::loop_step(
+                    &guard,
+                    state.get_selected_item(),
+                    state.get_selected_slide(),
+                ) else {
+                    return false;
+                };
+
+                state.set_selected_item(item);
+                state.set_selected_slide(slide);
+                set_navigation(&state, &guard);
+                true
+            }
+        });
+
+        state.on_selection_changed({
+            let instance = self.clone();
+            move || {
+                if let Some(window) = instance.window.upgrade() {
+                    let guard = instance.schedule_cache.lock().unwrap();
+                    set_navigation(&window.global::<ScheduleState>(), &guard);
+                }
+            }
+        });
+
+        state.on_set_advance({
+            let instance = self.clone();
+            move |index, seconds| {
+                let mut guard = instance.schedule_cache.lock().unwrap();
+                let Some(item) = guard.get(index as usize) else {
+                    return;
+                };
+                let seconds = seconds.max(0);
+                if item.advance == seconds {
+                    return;
+                }
+
+                let mut history = instance.history.borrow_mut();
+                history.record(&guard, instance.current_path.lock().unwrap().as_deref());
+                guard[index as usize].advance = seconds;
+                let path = instance.current_path.lock().unwrap().clone();
+                update_schedule(&instance.window, &instance.data, path, &history, &guard);
+            }
+        });
+
+        state.on_set_transition({
+            let instance = self.clone();
+            move |index, transition, duration| {
+                let mut guard = instance.schedule_cache.lock().unwrap();
+                let Some(item) = guard.get(index as usize) else {
+                    return;
+                };
+                let duration = duration.max(0);
+                if item.view_data.transition == transition
+                    && item.view_data.transition_duration == duration
+                {
+                    return;
+                }
+
+                let mut history = instance.history.borrow_mut();
+                history.record(&guard, instance.current_path.lock().unwrap().as_deref());
+                let view_data = &mut guard[index as usize].view_data;
+                view_data.transition = transition;
+                view_data.transition_duration = duration;
+                let path = instance.current_path.lock().unwrap().clone();
+                update_schedule(&instance.window, &instance.data, path, &history, &guard);
+            }
+        });
+
+        state.on_open_schedule({
+            let instance = self.clone();
+            move || {
+                let path = FileDialog::new()
+                    .add_filter("Schedule", &[SCHEDULE_EXTENSION])
+                    .set_directory(schedules_dir(&instance.data))
+                    .pick_file();
+
+                if let Some(path) = path {
+                    instance.open(&path);
+                }
+            }
+        });
+
+        state.on_open_recent_schedule({
+            let instance = self.clone();
+            move |index| {
+                let settings = instance.data.load::<AppSettings>();
+                let Some(path) = recent_schedules(&settings).get(index as usize).cloned() else {
+                    return;
+                };
+                instance.open(Path::new(&path));
+            }
+        });
+
+        state.on_new_from_template({
+            let instance = self.clone();
+            move |index| {
+                let Some(path) = list_templates(&instance.data).get(index as usize).cloned() else {
+                    return;
+                };
+                instance.new_from_template(&path);
+            }
+        });
+
+        state.on_save_template({
+            let instance = self.clone();
+            move || {
+                let path = FileDialog::new()
+                    .add_filter("Schedule Template", &[SCHEDULE_EXTENSION])
+                    .set_directory(templates_dir(&instance.data))
+                    .save_file();
+
+                let Some(path) = path else {
+                    return;
+                };
+                let mut template =
+                    ScheduleFile::from_items(&instance.schedule_cache.lock().unwrap());
+                template.branding = instance.active_branding();
+                if template.save(&path.with_extension(SCHEDULE_EXTENSION)) {
+                    set_templates(&instance.window, &instance.data);
+                }
+            }
+        });
+
+        state.on_save_schedule({
+            let instance = self.clone();
+            move || {
+                let path = instance.current_path.lock().unwrap().clone();
+                if let Some(path) = path.or_else(|| pick_save_path(&instance.data)) {
+                    instance.save(path);
+                }
+            }
+        });
+
+        state.on_save_schedule_as({
+            let instance = self.clone();
+            move || {
+                if let Some(path) = pick_save_path(&instance.data) {
+                    instance.save(path);
                 }
             }
         });
+
+        state.on_export_package({
+            let instance = self.clone();
+            move || {
+                let path = FileDialog::new()
+                    .add_filter("Schedule Package", &[PACKAGE_EXTENSION])
+                    .set_file_name(format!(
+                        "{}.{PACKAGE_EXTENSION}",
+                        schedule_name(instance.current_path.lock().unwrap().as_deref())
+                    ))
+                    .save_file();
+
+                if let Some(path) = path {
+                    instance.export_package(&path.with_extension(PACKAGE_EXTENSION));
+                }
+            }
+        });
+
+        state.on_export_run_sheet({
+            let instance = self.clone();
+            move || {
+                let name = schedule_name(instance.current_path.lock().unwrap().as_deref());
+                let path = FileDialog::new()
+                    .add_filter("Run Sheet", &[RUN_SHEET_EXTENSION])
+                    .set_file_name(format!("{name}.{RUN_SHEET_EXTENSION}"))
+                    .save_file();
+
+                if let Some(path) = path {
+                    instance.export_run_sheet(&name, &path.with_extension(RUN_SHEET_EXTENSION));
+                }
+            }
+        });
+
+        state.on_import_plan({
+            let instance = self.clone();
+            move || {
+                let path = FileDialog::new()
+                    .add_filter("Service Plan", PLAN_EXTENSIONS)
+                    .pick_file();
+
+                if let Some(path) = path {
+                    instance.import_plan(&path);
+                }
+            }
+        });
+
+        state.on_import_deck({
+            let instance = self.clone();
+            move || {
+                let path = FileDialog::new()
+                    .add_filter("Presentation", DECK_EXTENSIONS)
+                    .pick_file();
+
+                if let Some(path) = path {
+                    instance.import_deck(&path);
+                }
+            }
+        });
+
+        state.on_import_package({
+            let instance = self.clone();
+            move || {
+                let path = FileDialog::new()
+                    .add_filter("Schedule Package", &[PACKAGE_EXTENSION])
+                    .pick_file();
+
+                if let Some(path) = path {
+                    instance.import_package(&path);
+                }
+            }
+        });
+    }
+
+    fn import_plan(&self, path: &Path) {
+        let Ok(entries) = plan::load(path)
+            .inspect_err(|e| error!("Cannot import service plan {}: {e}", path.display()))
+        else {
+            return;
+        };
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+        // only the look of the preview is kept, not what it is showing
+        let base = ViewData {
+            content: SharedString::default(),
+            verse: SharedString::default(),
+            ..window.global::<ViewState>().get_shared_view()
+        };
+
+        let mut items = Vec::new();
+        let mut unmatched = Vec::new();
+        {
+            let songs = self.song_manager.songs_cache.lock().unwrap();
+            for entry in entries {
+                let is_reading = entry.kind == PlanKind::Reading
+                    || (entry.kind == PlanKind::Other && plan::looks_like_reference(&entry.title));
+
+                let item = match entry.kind {
+                    PlanKind::Header => Some(ScheduledItem {
+                        kind: ScheduledKind::Header,
+                        label: entry.title.to_shared_string(),
+                        ..Default::default()
+                    }),
+                    PlanKind::Song => {
+                        plan::find_song(&songs, &entry.title).map(|song| ScheduledItem {
+                            kind: ScheduledKind::Song,
+                            label: song.path.clone(),
+                            song: song.path.clone(),
+                            view_data: ViewData {
+                                content: song.content.first().cloned().unwrap_or_default(),
+                                ..base.clone()
+                            },
+                            slides: ModelRc::from(song.content.as_slice()),
+                            ..Default::default()
+                        })
+                    }
+                    _ if is_reading => self.find_reading(&entry.title, &base),
+                    _ => None,
+                };
+
+                // an unmatched row keeps its place as an empty slot, never shown on output
+                let item = item.unwrap_or_else(|| {
+                    unmatched.push(entry.title.to_shared_string());
+                    ScheduledItem {
+                        kind: ScheduledKind::Placeholder,
+                        label: entry.title.to_shared_string(),
+                        ..Default::default()
+                    }
+                });
+
+                items.push(ScheduledItem {
+                    id: self.id_counter.fetch_add(1, Ordering::SeqCst),
+                    notes: entry.notes.to_shared_string(),
+                    ..item
+                });
+            }
+        }
+
+        if items.is_empty() {
+            return;
+        }
+
+        let mut guard = self.schedule_cache.lock().unwrap();
+        let mut history = self.history.borrow_mut();
+        history.record(&guard, self.current_path.lock().unwrap().as_deref());
+        guard.extend(items);
+        let path = self.current_path.lock().unwrap().clone();
+        update_schedule(&self.window, &self.data, path, &history, &guard);
+
+        window
+            .global::<ScheduleState>()
+            .set_import_report(ModelRc::from(unmatched.as_slice()));
     }
+
+    /// Adds the deck under a header named after it, one text item per slide with the
+    /// slide's picture as its background and the current style for everything else.
+    fn import_deck(&self, path: &Path) {
+        let name = schedule_name(Some(path));
+        // every import gets its own folder, importing again keeps the first one's pictures
+        let import_dir = |dir: &str| self.data.data_dir(&["imports", dir]);
+        let Some(dir) = free_name(&name, |dir| !import_dir(dir).exists()) else {
+            return;
+        };
+        let media_dir = import_dir(&dir);
+
+        let Ok(slides) = deck::load(path, &media_dir)
+            .inspect_err(|e| error!("Cannot import presentation {}: {e}", path.display()))
+        else {
+            return;
+        };
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+        // the slides bring their own text, a reference left on the preview does not belong
+        let base = ViewData {
+            verse: SharedString::default(),
+            ..window.global::<ViewState>().get_shared_view()
+        };
+
+        let mut items = vec![ScheduledItem {
+            id: self.id_counter.fetch_add(1, Ordering::SeqCst),
+            kind: ScheduledKind::Header,
+            label: name,
+            ..Default::default()
+        }];
+        for (index, slide) in slides.into_iter().enumerate() {
+            let mut view_data = ViewData {
+                content: slide.text.to_shared_string(),
+                ..base.clone()
+            };
+            if let Some(image) = slide.image {
+                view_data.path = image.to_string_lossy().to_shared_string();
+                view_data.show_img = true;
+                view_data.img_bg = Image::load_from_path(&image).unwrap_or_default();
+            }
+
+            let label = slide
+                .text
+                .lines()
+                .next()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("Slide {}", index + 1));
+            items.push(ScheduledItem {
+                id: self.id_counter.fetch_add(1, Ordering::SeqCst),
+                kind: ScheduledKind::Text,
+                label: label.to_shared_string(),
+                view_data,
+                ..Default::default()
+            });
+        }
+
+        let mut guard = self.schedule_cache.lock().unwrap();
+        let mut history = self.history.borrow_mut();
+        history.record(&guard, self.current_path.lock().unwrap().as_deref());
+        guard.extend(items);
+        let path = self.current_path.lock().unwrap().clone();
+        update_schedule(&self.window, &self.data, path, &history, &guard);
+    }
+
+    fn find_reading(&self, reference: &str, base: &ViewData) -> Option<ScheduledItem> {
+        let verses = SearchedVerse::from_search(reference, self.database.verse_index())
+            .inspect_err(|e| error!("Cannot search reading {reference}: {e}"))
+            .ok()?;
+        let bible = &verses.first()?.bible.id;
+
+        let slides = verses
+            .iter()
+            .filter(|v| &v.bible.id == bible)
+            .map(|v| v.text.to_shared_string())
+            .collect::<Vec<_>>();
+
+        Some(ScheduledItem {
+            kind: ScheduledKind::Verse,
+            label: reference.to_shared_string(),
+            view_data: ViewData {
+                content: slides.first().cloned().unwrap_or_default(),
+                verse: reference.to_shared_string(),
+                ..base.clone()
+            },
+            slides: ModelRc::from(slides.as_slice()),
+            ..Default::default()
+        })
+    }
+
+    fn resolve(&self, schedule: ScheduleFile) -> Vec<ScheduledItem> {
+        let songs = self.song_manager.songs_cache.lock().unwrap();
+        schedule.into_items(&songs)
+    }
+
+    fn open(&self, path: &Path) {
+        let Some(schedule) = ScheduleFile::load(path) else {
+            return;
+        };
+
+        let mut guard = self.schedule_cache.lock().unwrap();
+        let mut history = self.history.borrow_mut();
+        history.record(&guard, self.current_path.lock().unwrap().as_deref());
+        *guard = self.resolve(schedule);
+        *self.current_path.lock().unwrap() = Some(path.to_path_buf());
+
+        let next_id = guard.iter().map(|i| i.id).max().unwrap_or_default() + 1;
+        self.id_counter.store(next_id, Ordering::SeqCst);
+
+        update_schedule(
+            &self.window,
+            &self.data,
+            Some(path.to_path_buf()),
+            &history,
+            &guard,
+        );
+        push_recent_schedule(&self.window, &self.data, path);
+    }
+
+    fn new_from_template(&self, path: &Path) {
+        let Some(mut template) = ScheduleFile::load(path) else {
+            return;
+        };
+        if let Some(name) = template.branding.take() {
+            self.apply_branding(&name);
+        }
+
+        let mut guard = self.schedule_cache.lock().unwrap();
+        let mut history = self.history.borrow_mut();
+        history.record(&guard, self.current_path.lock().unwrap().as_deref());
+        *guard = self
+            .resolve(template)
+            .into_iter()
+            .map(|item| ScheduledItem {
+                id: self.id_counter.fetch_add(1, Ordering::SeqCst),
+                ..item
+            })
+            .collect();
+        *self.current_path.lock().unwrap() = None;
+
+        update_schedule(&self.window, &self.data, None, &history, &guard);
+    }
+
+    fn active_branding(&self) -> Option<String> {
+        let window = self.window.upgrade()?;
+        let settings = window.global::<Settings>();
+        let index = usize::try_from(settings.get_selected_branding()).ok()?;
+        Some(settings.get_branding_presets().row_data(index)?.to_string())
+    }
+
+    /// Goes through the main window so the media manager restyles it and picks the logo.
+    fn apply_branding(&self, name: &str) {
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+        let settings = window.global::<Settings>();
+        let index = settings
+            .get_branding_presets()
+            .iter()
+            .position(|p| p == name);
+        match index {
+            Some(index) => settings.invoke_apply_branding(index as i32),
+            None => error!("Branding preset {name} not found"),
+        }
+    }
+
+    fn save(&self, path: PathBuf) {
+        let guard = self.schedule_cache.lock().unwrap();
+        if !ScheduleFile::from_items(&guard).save(&path) {
+            return;
+        }
+
+        *self.current_path.lock().unwrap() = Some(path.clone());
+        update_schedule(
+            &self.window,
+            &self.data,
+            Some(path.clone()),
+            &self.history.borrow(),
+            &guard,
+        );
+        push_recent_schedule(&self.window, &self.data, &path);
+    }
+
+    fn export_package(&self, dest: &Path) {
+        let schedule = ScheduleFile::from_items(&self.schedule_cache.lock().unwrap());
+        let songs = self.song_manager.songs_cache.lock().unwrap();
+
+        _ = package::export(&schedule, &songs, dest)
+            .inspect_err(|e| error!("Cannot export schedule package {}: {e}", dest.display()));
+    }
+
+    fn export_run_sheet(&self, name: &str, dest: &Path) {
+        let html = export::run_sheet_html(name, &self.schedule_cache.lock().unwrap());
+
+        if std::fs::write(dest, html)
+            .inspect_err(|e| error!("Cannot write run sheet {}: {e}", dest.display()))
+            .is_ok()
+        {
+            _ = open::that(dest).inspect_err(|e| error!("Cannot open run sheet: {e}"));
+        }
+    }
+
+    fn import_package(&self, src: &Path) {
+        let stem = schedule_name(Some(src));
+        // an earlier import of a package with the same name keeps its schedule and media
+        let schedule_path = |name: &str| {
+            schedules_dir(&self.data)
+                .join(name)
+                .with_extension(SCHEDULE_EXTENSION)
+        };
+        let Some(name) = free_name(&stem, |name| {
+            !self.data.data_dir(&["packages", name]).exists() && !schedule_path(name).exists()
+        }) else {
+            return;
+        };
+        let extract_dir = self.data.data_dir(&["packages", name.as_str()]);
+        let path = schedule_path(&name);
+
+        let Ok((schedule, songs)) = package::import(src, &extract_dir)
+            .inspect_err(|e| error!("Cannot import schedule package {}: {e}", src.display()))
+        else {
+            return;
+        };
+
+        self.song_manager.import_songs(&songs);
+        if schedule.save(&path) {
+            self.open(&path);
+        }
+    }
+}
+
+/// `stem`, or `stem (2)`, `stem (3)`... for the first name `is_free` accepts.
+fn free_name(stem: &str, is_free: impl Fn(&str) -> bool) -> Option<String> {
+    (1..)
+        .map(|n| match n {
+            1 => stem.to_string(),
+            n => format!("{stem} ({n})"),
+        })
+        .find(|name| is_free(name))
+}
+
+fn schedules_dir(data: &UserData) -> PathBuf {
+    let dir = data.data_dir(&["schedules"]);
+    if !dir.exists() {
+        _ = std::fs::create_dir_all(&dir);
+    }
+    dir
+}
+
+fn templates_dir(data: &UserData) -> PathBuf {
+    let dir = data.data_dir(&["templates"]);
+    if !dir.exists() {
+        _ = std::fs::create_dir_all(&dir);
+    }
+    dir
+}
+
+fn list_templates(data: &UserData) -> Vec<PathBuf> {
+    let mut templates = std::fs::read_dir(templates_dir(data))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == SCHEDULE_EXTENSION))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    templates.sort();
+    templates
+}
+
+fn set_templates(window: &Weak<MainWindow>, data: &UserData) {
+    let Some(window) = window.upgrade() else {
+        return;
+    };
+
+    let names = list_templates(data)
+        .iter()
+        .map(|p| schedule_name(Some(p)))
+        .collect::<Vec<_>>();
+
+    window
+        .global::<ScheduleState>()
+        .set_templates(ModelRc::from(names.as_slice()));
+}
+
+/// Fills the selected placeholder slot, otherwise appends the item at the end
+fn place_item(window: &MainWindow, items: &mut Vec<ScheduledItem>, mut item: ScheduledItem) {
+    let selected = window.global::<ScheduleState>().get_selected_item();
+    let slot = usize::try_from(selected).ok().filter(|&i| {
+        items
+            .get(i)
+            .is_some_and(|s| s.kind == ScheduledKind::Placeholder)
+    });
+
+    match slot {
+        Some(index) => {
+            if item.notes.is_empty() {
+                item.notes = items[index].notes.clone();
+            }
+            items[index] = item;
+        }
+        None => items.push(item),
+    }
+}
+
+fn pick_save_path(data: &UserData) -> Option<PathBuf> {
+    FileDialog::new()
+        .add_filter("Schedule", &[SCHEDULE_EXTENSION])
+        .set_directory(schedules_dir(data))
+        .save_file()
+        .map(|p| p.with_extension(SCHEDULE_EXTENSION))
+}
+
+fn update_schedule(
+    window: &Weak<MainWindow>,
+    data: &UserData,
+    path: Option<PathBuf>,
+    history: &History,
+    items: &[ScheduledItem],
+) {
+    if let Some(window) = window.upgrade() {
+        let state = window.global::<ScheduleState>();
+        let selected_id = usize::try_from(state.get_selected_item())
+            .ok()
+            .and_then(|index| state.get_items().row_data(index))
+            .map(|item| item.id);
+        state.set_items(ModelRc::from(items));
+        clamp_selection(&state, selected_id, items);
+        state.set_name(schedule_name(path.as_deref()));
+        state.set_can_undo(history.can_undo());
+        state.set_can_redo(history.can_redo());
+        set_navigation(&state, items);
+    }
+    render_missing_pages(window, items);
+
+    data.save(&ScheduleAutosave {
+        path: path.map(|p| p.to_string_lossy().into_owned()),
+        schedule: ScheduleFile::from_items(items),
+    });
+}
+
+/// Documents whose pages are not in the cache yet, after a restore, an import or a relink.
+fn render_missing_pages(window: &Weak<MainWindow>, items: &[ScheduledItem]) {
+    let documents = items
+        .iter()
+        .filter(|i| i.view_data.images.row_count() == 0)
+        .filter(|i| is_document(&i.view_data.path))
+        .map(|i| PathBuf::from(i.view_data.path.as_str()))
+        .collect::<HashSet<_>>();
+    render_pages_in_background(window.clone(), documents.into_iter().collect());
+}
+
+/// Keeps the selection inside `items`, the slide starts over once the selected item changed.
+fn clamp_selection(state: &ScheduleState, selected_id: Option<i32>, items: &[ScheduledItem]) {
+    let Some(last) = items.len().checked_sub(1) else {
+        state.set_selected_item(-1);
+        state.set_selected_slide(-1);
+        return;
+    };
+    let Ok(selected) = usize::try_from(state.get_selected_item()) else {
+        state.set_selected_slide(-1);
+        return;
+    };
+
+    let index = selected.min(last);
+    let item = &items[index];
+    let slide = state.get_selected_slide();
+    if Some(item.id) != selected_id || slide >= item.slides.row_count() as i32 {
+        state.set_selected_slide(if item.slides.row_count() > 0 { 0 } else { -1 });
+    }
+    state.set_selected_item(index as i32);
+}
+
+/// Enables go live previous and next only when `navigation::step` reaches something.
+fn set_navigation(state: &ScheduleState, items: &[ScheduledItem]) {
+    let (item, slide) = (state.get_selected_item(), state.get_selected_slide());
+    state.set_can_step_previous(navigation::step(items, item, slide, -1).is_some());
+    state.set_can_step_next(navigation::step(items, item, slide, 1).is_some());
+}
+
+fn recent_schedules(settings: &AppSettings) -> Vec<String> {
+    settings
+        .recent_schedules
+        .iter()
+        .filter(|p| Path::new(p).exists())
+        .cloned()
+        .collect()
+}
+
+fn push_recent_schedule(window: &Weak<MainWindow>, data: &UserData, path: &Path) {
+    let path = path.to_string_lossy().into_owned();
+    let mut settings = data.load::<AppSettings>();
+
+    settings.recent_schedules.retain(|p| p != &path);
+    settings.recent_schedules.insert(0, path);
+    settings.recent_schedules.truncate(MAX_RECENT_SCHEDULES);
+    data.save(&settings);
+
+    set_recent_schedules(window, &settings);
+}
+
+fn set_recent_schedules(window: &Weak<MainWindow>, settings: &AppSettings) {
+    let Some(window) = window.upgrade() else {
+        return;
+    };
+
+    let names = recent_schedules(settings)
+        .iter()
+        .map(|p| schedule_name(Some(Path::new(p))))
+        .collect::<Vec<_>>();
+
+    window
+        .global::<ScheduleState>()
+        .set_recent_schedules(ModelRc::from(names.as_slice()));
 }
@@ -0,0 +1,422 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek};
+use std::path::{Path, PathBuf};
+
+use roxmltree::{Document, Node};
+use tracing::{error, warn};
+use zip::ZipArchive;
+
+pub const DECK_EXTENSIONS: &[&str] = &["pptx", "odp"];
+
+const RELATIONSHIPS_NS: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
+const TEXT_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:text:1.0";
+
+pub struct DeckSlide {
+    pub text: String,
+    // the first embedded picture, extracted next to the other imported media
+    pub image: Option<PathBuf>,
+}
+
+/// Reads the slides of a PowerPoint or Impress deck in presentation order, extracting
+/// their pictures into `media_dir`, which is only created once a picture is extracted.
+pub fn load(path: &Path, media_dir: &Path) -> io::Result<Vec<DeckSlide>> {
+    let mut archive = ZipArchive::new(File::open(path)?).map_err(io::Error::other)?;
+
+    let is_odp = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("odp"));
+    if is_odp {
+        load_odp(&mut archive, media_dir)
+    } else {
+        load_pptx(&mut archive, media_dir)
+    }
+}
+
+fn load_pptx<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    media_dir: &Path,
+) -> io::Result<Vec<DeckSlide>> {
+    let presentation = read_entry(archive, "ppt/presentation.xml")?;
+    let presentation = Document::parse(&presentation).map_err(io::Error::other)?;
+    let targets = relationships(archive, "ppt/_rels/presentation.xml.rels")?;
+
+    let slide_paths = presentation
+        .descendants()
+        .filter(|n| n.has_tag_name("sldId"))
+        .filter_map(|n| targets.get(n.attribute((RELATIONSHIPS_NS, "id"))?))
+        .map(|target| resolve("ppt", target))
+        .collect::<Vec<_>>();
+
+    let mut slides = Vec::new();
+    for slide_path in slide_paths {
+        let content = read_entry(archive, &slide_path)?;
+        let slide = Document::parse(&content).map_err(io::Error::other)?;
+
+        let text = paragraphs(
+            slide.descendants().filter(|n| n.has_tag_name("p")),
+            pptx_text,
+        );
+
+        let (dir, file) = slide_path
+            .rsplit_once('/')
+            .unwrap_or(("", slide_path.as_str()));
+        let slide_targets = relationships(archive, &format!("{dir}/_rels/{file}.rels"))?;
+        let pictures = slide
+            .descendants()
+            .filter(|n| n.has_tag_name("blip"))
+            .filter_map(|n| slide_targets.get(n.attribute((RELATIONSHIPS_NS, "embed"))?))
+            .map(|target| resolve(dir, target))
+            .collect::<Vec<_>>();
+        let image = first_image(archive, slides.len() + 1, &pictures, media_dir);
+
+        slides.push(DeckSlide { text, image });
+    }
+    Ok(slides)
+}
+
+fn load_odp<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    media_dir: &Path,
+) -> io::Result<Vec<DeckSlide>> {
+    let content = read_entry(archive, "content.xml")?;
+    let content = Document::parse(&content).map_err(io::Error::other)?;
+
+    let mut slides = Vec::new();
+    for page in content.descendants().filter(|n| n.has_tag_name("page")) {
+        // speaker notes live inside the page but are not shown
+        let shown = page
+            .descendants()
+            .filter(|n| !n.ancestors().any(|a| a.has_tag_name("notes")));
+
+        let text = paragraphs(shown.clone().filter(|n| n.has_tag_name("p")), odp_text);
+        let pictures = shown
+            .filter(|n| n.has_tag_name("image"))
+            .filter_map(|n| n.attribute((XLINK_NS, "href")))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let image = first_image(archive, slides.len() + 1, &pictures, media_dir);
+
+        slides.push(DeckSlide { text, image });
+    }
+    Ok(slides)
+}
+
+/// Joins the text of each non empty paragraph, one per line. `text` gives what each node
+/// inside a paragraph adds to it.
+fn paragraphs<'a, 'i: 'a>(
+    nodes: impl Iterator<Item = Node<'a, 'i>>,
+    text: impl Fn(Node<'a, 'i>) -> Option<Cow<'a, str>>,
+) -> String {
+    nodes
+        .map(|p| p.descendants().filter_map(&text).collect::<String>())
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Runs hold the text with its spaces, breaks split a paragraph into lines.
+fn pptx_text<'a>(node: Node<'a, '_>) -> Option<Cow<'a, str>> {
+    match node.tag_name().name() {
+        "t" => node.text().map(Cow::Borrowed),
+        "br" => Some(Cow::Borrowed("\n")),
+        _ => None,
+    }
+}
+
+/// Impress collapses repeated spaces in the text itself, runs of them and tabs are
+/// elements of their own.
+fn odp_text<'a>(node: Node<'a, '_>) -> Option<Cow<'a, str>> {
+    if node.is_text() {
+        return node.text().map(Cow::Borrowed);
+    }
+    match node.tag_name().name() {
+        "s" => {
+            let count = node
+                .attribute((TEXT_NS, "c"))
+                .and_then(|c| c.parse().ok())
+                .unwrap_or(1);
+            Some(Cow::Owned(" ".repeat(count)))
+        }
+        "tab" => Some(Cow::Borrowed("\t")),
+        "line-break" => Some(Cow::Borrowed("\n")),
+        _ => None,
+    }
+}
+
+/// Maps relationship ids to their targets, a part without relationships has none.
+fn relationships<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> io::Result<HashMap<String, String>> {
+    let Ok(content) = read_entry(archive, name) else {
+        return Ok(HashMap::new());
+    };
+    let document = Document::parse(&content).map_err(io::Error::other)?;
+
+    Ok(document
+        .descendants()
+        .filter(|n| n.has_tag_name("Relationship"))
+        .filter_map(|n| {
+            Some((
+                n.attribute("Id")?.to_string(),
+                n.attribute("Target")?.to_string(),
+            ))
+        })
+        .collect())
+}
+
+/// Resolves a relationship target relative to the folder of the part that references it.
+fn resolve<'a>(dir: &'a str, target: &'a str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+
+    let mut parts = dir.split('/').filter(|p| !p.is_empty()).collect::<Vec<_>>();
+    for part in target.split('/') {
+        match part {
+            ".." => {
+                parts.pop();
+            }
+            "." | "" => {}
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// A slide shows a single picture, the ones after the first that can be extracted are
+/// only logged.
+fn first_image<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    slide: usize,
+    pictures: &[String],
+    media_dir: &Path,
+) -> Option<PathBuf> {
+    let mut pictures = pictures.iter();
+    let image = pictures
+        .by_ref()
+        .find_map(|name| extract_image(archive, name, media_dir));
+
+    let skipped = pictures
+        .filter(|name| image::ImageFormat::from_path(name).is_ok())
+        .count();
+    if skipped > 0 {
+        warn!("Slide {slide} has {skipped} more pictures, only the first one is imported");
+    }
+    image
+}
+
+/// Copies a picture out of the deck, skipping formats that cannot be shown such as EMF.
+fn extract_image<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+    media_dir: &Path,
+) -> Option<PathBuf> {
+    image::ImageFormat::from_path(name).ok()?;
+
+    let mut bytes = Vec::new();
+    archive.by_name(name).ok()?.read_to_end(&mut bytes).ok()?;
+
+    let dest = media_dir.join(Path::new(name).file_name()?);
+    std::fs::create_dir_all(media_dir)
+        .and_then(|_| std::fs::write(&dest, bytes))
+        .inspect_err(|e| error!("Cannot extract {}: {e}", dest.display()))
+        .ok()?;
+    Some(dest)
+}
+
+fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> io::Result<String> {
+    let mut content = String::new();
+    archive
+        .by_name(name)
+        .map_err(io::Error::other)?
+        .read_to_string(&mut content)?;
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    use super::*;
+
+    const PML: &str = r#"xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships""#;
+    const ODF: &str = r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0" xmlns:presentation="urn:oasis:names:tc:opendocument:xmlns:presentation:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:xlink="http://www.w3.org/1999/xlink""#;
+
+    fn archive(entries: &[(&str, &str)]) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        ZipArchive::new(writer.finish().unwrap()).unwrap()
+    }
+
+    fn media_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("deck-{name}-{}", std::process::id()))
+    }
+
+    fn slide(body: &str) -> String {
+        format!(r#"<p:sld {PML}><p:cSld><p:spTree>{body}</p:spTree></p:cSld></p:sld>"#)
+    }
+
+    fn rels(targets: &[(&str, &str)]) -> String {
+        let targets = targets
+            .iter()
+            .map(|(id, target)| format!(r#"<Relationship Id="{id}" Target="{target}"/>"#))
+            .collect::<String>();
+        format!(
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{targets}</Relationships>"#
+        )
+    }
+
+    #[test]
+    fn targets_resolve_against_the_referencing_folder() {
+        assert_eq!(resolve("ppt", "slides/slide1.xml"), "ppt/slides/slide1.xml");
+        assert_eq!(
+            resolve("ppt/slides", "../media/image1.png"),
+            "ppt/media/image1.png"
+        );
+        assert_eq!(resolve("ppt/slides", "/ppt/media/a.png"), "ppt/media/a.png");
+        assert_eq!(
+            resolve("ppt", "./slides//slide2.xml"),
+            "ppt/slides/slide2.xml"
+        );
+    }
+
+    #[test]
+    fn odp_paragraphs_keep_spaces_tabs_and_breaks() {
+        let xml = format!(
+            r#"<office:text {ODF}>
+                <text:p>Cuán<text:s/>grande<text:s text:c="3"/>es<text:tab/><text:span>Él</text:span></text:p>
+                <text:p> </text:p>
+                <text:p>Mi<text:line-break/>Dios</text:p>
+            </office:text>"#
+        );
+        let document = Document::parse(&xml).unwrap();
+        let text = paragraphs(
+            document.descendants().filter(|n| n.has_tag_name("p")),
+            odp_text,
+        );
+
+        assert_eq!(text, "Cuán grande   es\tÉl\nMi\nDios");
+    }
+
+    #[test]
+    fn pptx_paragraphs_join_runs_and_breaks() {
+        let xml = slide(
+            "<p:sp><p:txBody>\
+             <a:p><a:r><a:t>Santo </a:t></a:r><a:r><a:t>Santo</a:t></a:r><a:br/><a:r><a:t>Señor</a:t></a:r></a:p>\
+             <a:p><a:endParaRPr/></a:p>\
+             <a:p><a:r><a:t>Dios</a:t></a:r></a:p>\
+             </p:txBody></p:sp>",
+        );
+        let document = Document::parse(&xml).unwrap();
+        let text = paragraphs(
+            document.descendants().filter(|n| n.has_tag_name("p")),
+            pptx_text,
+        );
+
+        assert_eq!(text, "Santo Santo\nSeñor\nDios");
+    }
+
+    #[test]
+    fn pptx_slides_follow_the_presentation_order() {
+        let presentation = format!(
+            r#"<p:presentation {PML}><p:sldIdLst><p:sldId id="256" r:id="rId3"/><p:sldId id="257" r:id="rId2"/></p:sldIdLst></p:presentation>"#
+        );
+        let presentation_rels =
+            rels(&[("rId2", "slides/slide1.xml"), ("rId3", "slides/slide2.xml")]);
+        let first =
+            slide("<p:sp><p:txBody><a:p><a:r><a:t>Primera</a:t></a:r></a:p></p:txBody></p:sp>");
+        let second = slide(
+            r#"<p:sp><p:txBody><a:p><a:r><a:t>Segunda</a:t></a:r></a:p></p:txBody></p:sp>
+               <p:pic><p:blipFill><a:blip r:embed="rId1"/></p:blipFill></p:pic>"#,
+        );
+        let second_rels = rels(&[("rId1", "../media/image1.png")]);
+
+        let mut archive = archive(&[
+            ("ppt/presentation.xml", &presentation),
+            ("ppt/_rels/presentation.xml.rels", &presentation_rels),
+            ("ppt/slides/slide1.xml", &first),
+            ("ppt/slides/slide2.xml", &second),
+            ("ppt/slides/_rels/slide2.xml.rels", &second_rels),
+            ("ppt/media/image1.png", "png"),
+        ]);
+        let dir = media_dir("pptx");
+        let slides = load_pptx(&mut archive, &dir).unwrap();
+
+        assert_eq!(slides.len(), 2);
+        assert_eq!(slides[0].text, "Segunda");
+        assert_eq!(
+            slides[0].image.as_deref(),
+            Some(dir.join("image1.png").as_path())
+        );
+        assert_eq!(slides[1].text, "Primera");
+        assert!(slides[1].image.is_none());
+        _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn odp_slides_leave_out_speaker_notes() {
+        let content = format!(
+            r#"<office:document-content {ODF}><office:body><office:presentation>
+                <draw:page>
+                    <draw:frame><draw:image xlink:href="Pictures/fondo.jpg"/></draw:frame>
+                    <draw:frame><draw:text-box><text:p>Bienvenidos</text:p></draw:text-box></draw:frame>
+                    <presentation:notes><draw:frame><draw:text-box><text:p>Saludar primero</text:p></draw:text-box></draw:frame></presentation:notes>
+                </draw:page>
+                <draw:page>
+                    <draw:frame><draw:image xlink:href="Pictures/logo.emf"/></draw:frame>
+                </draw:page>
+            </office:presentation></office:body></office:document-content>"#
+        );
+
+        let mut archive = archive(&[
+            ("content.xml", &content),
+            ("Pictures/fondo.jpg", "jpg"),
+            ("Pictures/logo.emf", "emf"),
+        ]);
+        let dir = media_dir("odp");
+        let slides = load_odp(&mut archive, &dir).unwrap();
+
+        assert_eq!(slides.len(), 2);
+        assert_eq!(slides[0].text, "Bienvenidos");
+        assert_eq!(
+            slides[0].image.as_deref(),
+            Some(dir.join("fondo.jpg").as_path())
+        );
+        // pictures that cannot be shown are not extracted
+        assert_eq!(slides[1].text, "");
+        assert!(slides[1].image.is_none());
+        _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn decks_without_pictures_leave_no_folder() {
+        let content = format!(
+            r#"<office:document-content {ODF}><office:body><office:presentation>
+                <draw:page>
+                    <draw:frame><draw:text-box><text:p>Solo texto</text:p></draw:text-box></draw:frame>
+                </draw:page>
+            </office:presentation></office:body></office:document-content>"#
+        );
+
+        let mut archive = archive(&[("content.xml", &content)]);
+        let dir = media_dir("text-only");
+        let slides = load_odp(&mut archive, &dir).unwrap();
+
+        assert_eq!(slides.len(), 1);
+        assert!(!dir.exists());
+    }
+}
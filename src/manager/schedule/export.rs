@@ -0,0 +1,91 @@
+use std::fmt::Write;
+
+use slint::Model;
+use ui::{ScheduledItem, ScheduledKind};
+
+pub const RUN_SHEET_EXTENSION: &str = "html";
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+h1{font-size:1.6em;margin-bottom:1em}\
+table{width:100%;border-collapse:collapse}\
+th,td{text-align:left;vertical-align:top;padding:6px 8px;border-bottom:1px solid #ccc}\
+th{background:#eee}\
+.kind{white-space:nowrap;color:#555}\
+.sections{margin:4px 0 0 0;padding-left:1.2em;color:#444;font-size:.9em}\
+.sections li{white-space:pre-line;margin-bottom:4px}\
+.header td{background:#f4f4f4;font-weight:bold;font-size:1.1em;padding-top:14px}\
+.notes{margin-top:4px;font-style:italic;font-weight:normal;font-size:.9em;color:#a0522d}\
+@media print{body{margin:0}}";
+
+pub fn run_sheet_html(name: &str, items: &[ScheduledItem]) -> String {
+    let mut rows = String::new();
+    let mut position = 0;
+
+    for item in items {
+        if item.kind == ScheduledKind::Header {
+            _ = write!(
+                rows,
+                "<tr class=\"header\"><td colspan=\"3\">{}",
+                escape(&item.label)
+            );
+            write_notes(&mut rows, &item.notes);
+            rows.push_str("</td></tr>");
+            continue;
+        }
+
+        position += 1;
+        _ = write!(
+            rows,
+            "<tr><td>{position}</td><td class=\"kind\">{}</td><td>{}",
+            kind_name(item.kind),
+            escape(&item.label)
+        );
+        if item.kind == ScheduledKind::Placeholder {
+            rows.push_str(" <em>(empty)</em>");
+        }
+        if !item.view_data.verse.is_empty() && item.kind != ScheduledKind::Song {
+            _ = write!(rows, "<br><small>{}</small>", escape(&item.view_data.verse));
+        }
+        if item.slides.row_count() > 0 {
+            rows.push_str("<ol class=\"sections\">");
+            for section in item.slides.iter() {
+                _ = write!(rows, "<li>{}</li>", escape(&section));
+            }
+            rows.push_str("</ol>");
+        }
+        write_notes(&mut rows, &item.notes);
+        rows.push_str("</td></tr>");
+    }
+
+    let title = escape(name);
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title>\
+<style>{STYLE}</style></head><body><h1>{title}</h1>\
+<table><thead><tr><th>#</th><th>Kind</th><th>Item</th></tr></thead>\
+<tbody>{rows}</tbody></table></body></html>"
+    )
+}
+
+fn kind_name(kind: ScheduledKind) -> &'static str {
+    match kind {
+        ScheduledKind::Verse => "Verse",
+        ScheduledKind::Song => "Song",
+        ScheduledKind::Media => "Media",
+        ScheduledKind::Text => "Text",
+        ScheduledKind::Header => "Header",
+        ScheduledKind::Placeholder => "Slot",
+    }
+}
+
+fn write_notes(rows: &mut String, notes: &str) {
+    if !notes.is_empty() {
+        _ = write!(rows, "<div class=\"notes\">{}</div>", escape(notes));
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
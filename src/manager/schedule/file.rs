@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use slint::{Model, ModelRc, SharedString, ToSharedString};
+use tracing::error;
+use ui::{ScheduledItem, ScheduledKind, ViewData, ViewFontData};
+
+use crate::manager::MediaItem;
+use crate::manager::song::SongItem;
+
+pub const SCHEDULE_EXTENSION: &str = "json";
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleFile {
+    pub items: Vec<ScheduleEntry>,
+    /// Branding preset applied when a template is used
+    #[serde(default)]
+    pub branding: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    pub id: i32,
+    pub kind: ScheduledKind,
+    pub label: String,
+    /// Songs are stored by name so later lyric fixes are picked up on load
+    #[serde(default)]
+    pub song: Option<SongRef>,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub verse: String,
+    #[serde(default)]
+    pub slides: Vec<String>,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub advance: i32,
+    pub background: MediaItem,
+    pub font: ViewFontData,
+    pub verse_font: ViewFontData,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SongRef {
+    pub name: String,
+}
+
+impl ScheduleFile {
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path)
+            .inspect_err(|e| error!("Cannot read schedule {}: {e}", path.display()))
+            .ok()?;
+
+        serde_json::from_str(&content)
+            .inspect_err(|e| error!("Cannot parse schedule {}: {e}", path.display()))
+            .ok()
+    }
+
+    pub fn save(&self, path: &Path) -> bool {
+        let Ok(content) = serde_json::to_string_pretty(self) else {
+            return false;
+        };
+
+        std::fs::write(path, content)
+            .inspect_err(|e| error!("Cannot write schedule {}: {e}", path.display()))
+            .is_ok()
+    }
+
+    /// Points media at its new location, returns whether anything changed.
+    pub fn relink(&mut self, renames: &HashMap<String, String>) -> bool {
+        let mut changed = false;
+        for entry in &mut self.items {
+            let background = &mut entry.background;
+            for path in background
+                .path
+                .iter_mut()
+                .chain(background.images.iter_mut())
+            {
+                if let Some(new_path) = renames.get(path) {
+                    *path = new_path.clone();
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    pub fn from_items(items: &[ScheduledItem]) -> Self {
+        let items = items
+            .iter()
+            .map(|item| {
+                let song = (!item.song.is_empty()).then(|| SongRef {
+                    name: item.song.to_string(),
+                });
+
+                ScheduleEntry {
+                    id: item.id,
+                    kind: item.kind,
+                    label: item.label.to_string(),
+                    song,
+                    content: item.view_data.content.to_string(),
+                    verse: item.view_data.verse.to_string(),
+                    slides: item.slides.iter().map(|s| s.to_string()).collect(),
+                    notes: item.notes.to_string(),
+                    advance: item.advance,
+                    font: item.view_data.font.clone(),
+                    verse_font: item.view_data.verse_font.clone(),
+                    background: MediaItem::from(item.view_data.clone()),
+                }
+            })
+            .collect();
+
+        Self {
+            items,
+            branding: None,
+        }
+    }
+
+    pub fn into_items(self, songs: &[SongItem]) -> Vec<ScheduledItem> {
+        self.items
+            .into_iter()
+            .map(|entry| {
+                let mut view_data = ViewData::from(&entry.background);
+                let mut song_name = SharedString::default();
+                let mut slides = entry
+                    .slides
+                    .iter()
+                    .map(|s| s.to_shared_string())
+                    .collect::<Vec<_>>();
+
+                view_data.content = entry.content.to_shared_string();
+                view_data.verse = entry.verse.to_shared_string();
+                view_data.font = entry.font;
+                view_data.verse_font = entry.verse_font;
+
+                if let Some(song) = entry.song {
+                    if let Some(item) = songs.iter().find(|s| s.path.as_str() == song.name) {
+                        slides = item.content.clone();
+                    }
+                    if let Some(first) = slides.first() {
+                        view_data.content = first.clone();
+                    }
+                    song_name = song.name.to_shared_string();
+                }
+
+                ScheduledItem {
+                    id: entry.id,
+                    kind: entry.kind,
+                    label: entry.label.to_shared_string(),
+                    song: song_name,
+                    view_data,
+                    slides: ModelRc::from(slides.as_slice()),
+                    notes: entry.notes.to_shared_string(),
+                    advance: entry.advance,
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn schedule_name(path: Option<&Path>) -> SharedString {
+    path.and_then(Path::file_stem)
+        .map(|n| n.to_string_lossy().to_shared_string())
+        .unwrap_or_else(|| SharedString::from("Untitled"))
+}
@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+
+use ui::ScheduledItem;
+
+const MAX_HISTORY: usize = 50;
+
+/// The items together with the file they belong to, so undoing an open goes back to the
+/// previous file too.
+pub struct Snapshot {
+    pub items: Vec<ScheduledItem>,
+    pub path: Option<PathBuf>,
+}
+
+impl Snapshot {
+    fn new(items: &[ScheduledItem], path: Option<&Path>) -> Self {
+        Self {
+            items: items.to_vec(),
+            path: path.map(Path::to_path_buf),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl History {
+    pub fn record(&mut self, items: &[ScheduledItem], path: Option<&Path>) {
+        self.undo.push(Snapshot::new(items, path));
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn undo(&mut self, items: &[ScheduledItem], path: Option<&Path>) -> Option<Snapshot> {
+        let previous = self.undo.pop()?;
+        self.redo.push(Snapshot::new(items, path));
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, items: &[ScheduledItem], path: Option<&Path>) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.undo.push(Snapshot::new(items, path));
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(id: i32) -> Vec<ScheduledItem> {
+        vec![ScheduledItem {
+            id,
+            ..Default::default()
+        }]
+    }
+
+    #[test]
+    fn oldest_snapshots_are_dropped_past_the_cap() {
+        let mut history = History::default();
+        for id in 0..MAX_HISTORY as i32 + 10 {
+            history.record(&items(id), None);
+        }
+
+        let mut undone = Vec::new();
+        while let Some(snapshot) = history.undo(&[], None) {
+            undone.push(snapshot.items[0].id);
+        }
+
+        assert_eq!(undone.len(), MAX_HISTORY);
+        assert_eq!(undone.first(), Some(&(MAX_HISTORY as i32 + 9)));
+        assert_eq!(undone.last(), Some(&10));
+    }
+
+    #[test]
+    fn undo_and_redo_swap_the_current_items() {
+        let mut history = History::default();
+        history.record(&items(1), Some(Path::new("first.json")));
+
+        let previous = history.undo(&items(2), None).unwrap();
+        assert_eq!(previous.items[0].id, 1);
+        assert_eq!(previous.path.as_deref(), Some(Path::new("first.json")));
+        assert!(!history.can_undo());
+
+        let next = history.redo(&previous.items, None).unwrap();
+        assert_eq!(next.items[0].id, 2);
+        assert!(history.can_undo());
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn a_new_record_clears_redo() {
+        let mut history = History::default();
+        history.record(&items(1), None);
+        history.undo(&items(2), None);
+        assert!(history.can_redo());
+
+        history.record(&items(3), None);
+        assert!(!history.can_redo());
+        assert!(history.redo(&items(4), None).is_none());
+    }
+}
@@ -0,0 +1,144 @@
+use slint::Model;
+use ui::{ScheduledItem, ScheduledKind};
+
+/// Returns the `(item, slide)` reached after moving `offset` steps from the current selection.
+/// Slides inside the current item are visited first, headers and empty slots are never selected.
+pub fn step(items: &[ScheduledItem], item: i32, slide: i32, offset: i32) -> Option<(i32, i32)> {
+    let direction = offset.signum();
+    if direction == 0 {
+        return None;
+    }
+
+    if let Some(current) = usize::try_from(item).ok().and_then(|i| items.get(i)) {
+        let next_slide = slide + direction;
+        if next_slide >= 0 && (next_slide as usize) < current.slides.row_count() {
+            return Some((item, next_slide));
+        }
+    }
+
+    let mut next = item + direction;
+    while next >= 0 && (next as usize) < items.len() {
+        let candidate = &items[next as usize];
+        if !matches!(
+            candidate.kind,
+            ScheduledKind::Header | ScheduledKind::Placeholder
+        ) {
+            let slides = candidate.slides.row_count() as i32;
+            let slide = match (slides, direction > 0) {
+                (0, _) => -1,
+                (_, true) => 0,
+                (_, false) => slides - 1,
+            };
+            return Some((next, slide));
+        }
+        next += direction;
+    }
+
+    None
+}
+
+/// Moves forward like `step`, wrapping around to the first item of the current segment
+/// (the items between two headers) once its end is reached.
+pub fn loop_step(items: &[ScheduledItem], item: i32, slide: i32) -> Option<(i32, i32)> {
+    let Some(next) = step(items, item, slide, 1) else {
+        return segment_start(items, item);
+    };
+
+    let crosses_header = items
+        .iter()
+        .take(next.0 as usize)
+        .skip(item.max(0) as usize)
+        .any(|i| i.kind == ScheduledKind::Header);
+
+    if item >= 0 && crosses_header {
+        segment_start(items, item)
+    } else {
+        Some(next)
+    }
+}
+
+fn segment_start(items: &[ScheduledItem], item: i32) -> Option<(i32, i32)> {
+    let item = usize::try_from(item).ok()?;
+    let start = items
+        .get(..=item)?
+        .iter()
+        .rposition(|i| i.kind == ScheduledKind::Header)
+        .map_or(0, |header| header + 1);
+
+    step(items, start as i32 - 1, -1, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use slint::{ModelRc, SharedString};
+
+    use super::*;
+
+    fn item(kind: ScheduledKind, slides: usize) -> ScheduledItem {
+        let slides = (1..=slides)
+            .map(|s| SharedString::from(format!("Slide {s}")))
+            .collect::<Vec<_>>();
+        ScheduledItem {
+            kind,
+            slides: ModelRc::from(slides.as_slice()),
+            ..Default::default()
+        }
+    }
+
+    // header, song (2 slides), text, header, verse (3 slides), placeholder, media
+    fn schedule() -> Vec<ScheduledItem> {
+        vec![
+            item(ScheduledKind::Header, 0),
+            item(ScheduledKind::Song, 2),
+            item(ScheduledKind::Text, 0),
+            item(ScheduledKind::Header, 0),
+            item(ScheduledKind::Verse, 3),
+            item(ScheduledKind::Placeholder, 0),
+            item(ScheduledKind::Media, 0),
+        ]
+    }
+
+    #[test]
+    fn step_visits_slides_before_the_next_item() {
+        let items = schedule();
+
+        assert_eq!(step(&items, -1, -1, 1), Some((1, 0)));
+        assert_eq!(step(&items, 1, 0, 1), Some((1, 1)));
+        assert_eq!(step(&items, 1, 1, 1), Some((2, -1)));
+        assert_eq!(step(&items, 2, -1, 1), Some((4, 0)));
+        assert_eq!(step(&items, 4, 2, 1), Some((6, -1)));
+        assert_eq!(step(&items, 6, -1, 1), None);
+        assert_eq!(step(&items, 1, 0, 0), None);
+    }
+
+    #[test]
+    fn step_back_lands_on_the_last_slide() {
+        let items = schedule();
+
+        assert_eq!(step(&items, 6, -1, -1), Some((4, 2)));
+        assert_eq!(step(&items, 4, 0, -1), Some((2, -1)));
+        assert_eq!(step(&items, 2, -1, -1), Some((1, 1)));
+        assert_eq!(step(&items, 1, 0, -1), None);
+    }
+
+    #[test]
+    fn segment_start_is_the_first_item_after_the_header() {
+        let items = schedule();
+
+        assert_eq!(segment_start(&items, 2), Some((1, 0)));
+        assert_eq!(segment_start(&items, 6), Some((4, 0)));
+        assert_eq!(segment_start(&items, -1), None);
+    }
+
+    #[test]
+    fn loop_step_wraps_inside_the_segment() {
+        let items = schedule();
+
+        assert_eq!(loop_step(&items, 1, 1), Some((2, -1)));
+        // the next item is past a header, so the segment starts over
+        assert_eq!(loop_step(&items, 2, -1), Some((1, 0)));
+        // the end of the schedule wraps too
+        assert_eq!(loop_step(&items, 6, -1), Some((4, 0)));
+        assert_eq!(loop_step(&items, -1, -1), Some((1, 0)));
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::manager::song::SongItem;
+
+use super::ScheduleFile;
+
+pub const PACKAGE_EXTENSION: &str = "wspkg";
+
+const SCHEDULE_ENTRY: &str = "schedule.json";
+const MEDIA_DIR: &str = "media";
+const SONGS_DIR: &str = "songs";
+
+pub fn export(schedule: &ScheduleFile, songs: &[SongItem], dest: &Path) -> io::Result<()> {
+    let mut builder = tar::Builder::new(File::create(dest)?);
+    let mut schedule = schedule.clone();
+    let mut packed_media: HashMap<String, String> = HashMap::new();
+    let mut packed_songs: Vec<String> = Vec::new();
+
+    for entry in schedule.items.iter_mut() {
+        // slideshow images and document pages travel with the file they belong to
+        let background = &mut entry.background;
+        for path in background
+            .path
+            .iter_mut()
+            .chain(background.images.iter_mut())
+        {
+            if let Some(packed) = pack_media(&mut builder, &mut packed_media, path)? {
+                *path = packed;
+            }
+        }
+
+        let Some(song) = &entry.song else {
+            continue;
+        };
+        if packed_songs.contains(&song.name) {
+            continue;
+        }
+        let content = songs
+            .iter()
+            .find(|s| s.path.as_str() == song.name)
+            .map(|item| item.content.iter().map(|p| p.to_string()).collect())
+            .unwrap_or_else(|| entry.slides.clone())
+            .join("\n\n");
+        append_bytes(
+            &mut builder,
+            &format!("{SONGS_DIR}/{}.txt", song.name),
+            content.as_bytes(),
+        )?;
+        packed_songs.push(song.name.clone());
+    }
+
+    let content = serde_json::to_vec_pretty(&schedule).map_err(io::Error::other)?;
+    append_bytes(&mut builder, SCHEDULE_ENTRY, &content)?;
+
+    builder.finish()
+}
+
+/// Unpacks the package into `dest_dir`, returns the schedule pointing at the unpacked media
+/// and the song texts it carried.
+pub fn import(src: &Path, dest_dir: &Path) -> io::Result<(ScheduleFile, Vec<PathBuf>)> {
+    if !dest_dir.exists() {
+        std::fs::create_dir_all(dest_dir)?;
+    }
+
+    tar::Archive::new(File::open(src)?).unpack(dest_dir)?;
+
+    let content = std::fs::read_to_string(dest_dir.join(SCHEDULE_ENTRY))?;
+    let mut schedule: ScheduleFile = serde_json::from_str(&content).map_err(io::Error::other)?;
+    let mut songs = Vec::new();
+
+    for entry in schedule.items.iter_mut() {
+        if let Some(song) = &entry.song {
+            let text = dest_dir.join(SONGS_DIR).join(format!("{}.txt", song.name));
+            if text.is_file() && !songs.contains(&text) {
+                songs.push(text);
+            }
+        }
+
+        let background = &mut entry.background;
+        for path in background
+            .path
+            .iter_mut()
+            .chain(background.images.iter_mut())
+        {
+            if path.starts_with(MEDIA_DIR) {
+                *path = dest_dir.join(&*path).to_string_lossy().into_owned();
+            }
+        }
+    }
+
+    Ok((schedule, songs))
+}
+
+/// Adds the file once, returns its name inside the package or `None` if it is gone.
+fn pack_media(
+    builder: &mut tar::Builder<File>,
+    packed_media: &mut HashMap<String, String>,
+    path: &str,
+) -> io::Result<Option<String>> {
+    if let Some(packed) = packed_media.get(path) {
+        return Ok(Some(packed.clone()));
+    }
+
+    let source = Path::new(path);
+    if !source.is_file() {
+        return Ok(None);
+    }
+
+    let file_name = source
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let packed = format!("{MEDIA_DIR}/{}_{file_name}", packed_media.len());
+
+    builder.append_path_with_name(source, &packed)?;
+    packed_media.insert(path.to_string(), packed.clone());
+    Ok(Some(packed))
+}
+
+fn append_bytes(builder: &mut tar::Builder<File>, name: &str, bytes: &[u8]) -> io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use ui::{ScheduledKind, ViewData, ViewFontData};
+
+    use super::*;
+    use crate::manager::MediaItem;
+    use crate::manager::schedule::file::{ScheduleEntry, SongRef};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("package-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn song_entry(id: i32, name: &str, background: &Path) -> ScheduleEntry {
+        let mut media = MediaItem::from(ViewData::default());
+        media.path = Some(background.to_string_lossy().into_owned());
+
+        ScheduleEntry {
+            id,
+            kind: ScheduledKind::Song,
+            label: name.to_string(),
+            song: Some(SongRef {
+                name: name.to_string(),
+            }),
+            content: String::new(),
+            verse: String::new(),
+            slides: vec!["First verse".to_string()],
+            notes: String::new(),
+            advance: 0,
+            background: media,
+            font: ViewFontData::default(),
+            verse_font: ViewFontData::default(),
+        }
+    }
+
+    #[test]
+    fn song_backgrounds_travel_with_the_package() {
+        let source = temp_dir("source");
+        let first = source.join("first.png");
+        let second = source.join("second.png");
+        std::fs::write(&first, b"first").unwrap();
+        std::fs::write(&second, b"second").unwrap();
+
+        // the repeated song has a background of its own
+        let schedule = ScheduleFile {
+            items: vec![
+                song_entry(1, "Santo", &first),
+                song_entry(2, "Santo", &second),
+            ],
+            branding: None,
+        };
+        let package = source.join(format!("service.{PACKAGE_EXTENSION}"));
+        export(&schedule, &[], &package).unwrap();
+
+        let dest = temp_dir("dest");
+        let (imported, songs) = import(&package, &dest).unwrap();
+
+        assert_eq!(songs, vec![dest.join(SONGS_DIR).join("Santo.txt")]);
+        for (entry, content) in imported.items.iter().zip(["first", "second"]) {
+            let path = entry.background.path.as_deref().unwrap();
+            assert!(Path::new(path).starts_with(&dest));
+            assert_eq!(std::fs::read_to_string(path).unwrap(), content);
+        }
+
+        let _ = std::fs::remove_dir_all(source);
+        let _ = std::fs::remove_dir_all(dest);
+    }
+}
@@ -0,0 +1,242 @@
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::manager::song::SongItem;
+
+pub const PLAN_EXTENSIONS: &[&str] = &["csv", "json"];
+
+const TYPE_COLUMNS: &[&str] = &["type", "item type", "item_type", "kind", "category"];
+const TITLE_COLUMNS: &[&str] = &["title", "name", "item", "song"];
+const NOTES_COLUMNS: &[&str] = &["notes", "description", "details", "detail"];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PlanKind {
+    Header,
+    Song,
+    Reading,
+    Other,
+}
+
+pub struct PlanEntry {
+    pub kind: PlanKind,
+    pub title: String,
+    pub notes: String,
+}
+
+pub fn load(path: &Path) -> io::Result<Vec<PlanEntry>> {
+    let content = std::fs::read_to_string(path)?;
+
+    let is_json = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    let entries = if is_json {
+        parse_json(&content)?
+    } else {
+        parse_csv(&content)?
+    };
+
+    Ok(entries
+        .into_iter()
+        .filter(|e| !e.title.is_empty())
+        .collect())
+}
+
+fn parse_csv(content: &str) -> io::Result<Vec<PlanEntry>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(io::Error::other)?
+        .iter()
+        .map(|h| h.to_lowercase())
+        .collect::<Vec<_>>();
+    let column = |names: &[&str]| headers.iter().position(|h| names.contains(&h.as_str()));
+
+    let type_column = column(TYPE_COLUMNS);
+    let title_column = column(TITLE_COLUMNS)
+        .ok_or_else(|| io::Error::other("plan does not have a title column"))?;
+    let notes_column = column(NOTES_COLUMNS);
+
+    let mut entries = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(io::Error::other)?;
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|i| record.get(i))
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        entries.push(PlanEntry {
+            kind: plan_kind(&field(type_column)),
+            title: field(Some(title_column)),
+            notes: field(notes_column),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Accepts both the API shape (`{ "data": [{ "attributes": { ... } }] }`) and a plain array of items.
+fn parse_json(content: &str) -> io::Result<Vec<PlanEntry>> {
+    let root: Value = serde_json::from_str(content).map_err(io::Error::other)?;
+    let items = root
+        .get("data")
+        .or_else(|| root.get("items"))
+        .unwrap_or(&root)
+        .as_array()
+        .ok_or_else(|| io::Error::other("plan does not contain a list of items"))?;
+
+    let entries = items
+        .iter()
+        .map(|item| {
+            let attributes = item.get("attributes").unwrap_or(item);
+            let text = |names: &[&str]| {
+                names
+                    .iter()
+                    .find_map(|n| attributes.get(*n).and_then(Value::as_str))
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            };
+
+            PlanEntry {
+                kind: plan_kind(&text(&["item_type", "type", "kind", "category"])),
+                title: text(TITLE_COLUMNS),
+                notes: text(NOTES_COLUMNS),
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+fn plan_kind(kind: &str) -> PlanKind {
+    let kind = kind.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|w| kind.contains(w));
+
+    if has(&["header", "section", "sección", "seccion"]) {
+        PlanKind::Header
+    } else if has(&["song", "canción", "cancion", "alabanza"]) {
+        PlanKind::Song
+    } else if has(&[
+        "scripture",
+        "reading",
+        "bible",
+        "verse",
+        "lectura",
+        "escritura",
+    ]) {
+        PlanKind::Reading
+    } else {
+        PlanKind::Other
+    }
+}
+
+pub fn find_song<'a>(songs: &'a [SongItem], title: &str) -> Option<&'a SongItem> {
+    let title = normalize(title);
+    // a title of only punctuation would be contained in every song name
+    if title.is_empty() {
+        return None;
+    }
+
+    songs
+        .iter()
+        .find(|s| normalize(&s.path) == title)
+        .or_else(|| songs.iter().find(|s| normalize(&s.path).contains(&title)))
+}
+
+/// True for titles like "Juan 3:16" or "1 Corintios 13:4-7".
+pub fn looks_like_reference(title: &str) -> bool {
+    let Some((book, place)) = title.trim().rsplit_once(' ') else {
+        return false;
+    };
+
+    book.chars().any(char::is_alphabetic)
+        && place.starts_with(|c: char| c.is_ascii_digit())
+        && place
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ':' | '-' | ',' | '.'))
+}
+
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use slint::SharedString;
+
+    use super::*;
+
+    fn song(name: &str) -> SongItem {
+        SongItem {
+            content: Vec::new(),
+            path: SharedString::from(name),
+        }
+    }
+
+    #[test]
+    fn csv_columns_are_found_by_name() {
+        let content = "Notes,Item Type,Title\n\
+                       Welcome,Header,Opening\n\
+                       ,Song,Cuán Grande Es Él\n\
+                       Read slowly,Scripture,Juan 3:16\n";
+        let entries = parse_csv(content).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert!(entries[0].kind == PlanKind::Header);
+        assert_eq!(entries[0].title, "Opening");
+        assert_eq!(entries[0].notes, "Welcome");
+        assert!(entries[1].kind == PlanKind::Song);
+        assert_eq!(entries[1].title, "Cuán Grande Es Él");
+        assert!(entries[2].kind == PlanKind::Reading);
+        assert_eq!(entries[2].notes, "Read slowly");
+    }
+
+    #[test]
+    fn csv_rows_may_be_short() {
+        let entries = parse_csv("title,notes\nOnly title\n").unwrap();
+
+        assert_eq!(entries[0].title, "Only title");
+        assert_eq!(entries[0].notes, "");
+        assert!(entries[0].kind == PlanKind::Other);
+    }
+
+    #[test]
+    fn csv_without_title_column_is_rejected() {
+        assert!(parse_csv("type,notes\nSong,Key of G\n").is_err());
+    }
+
+    #[test]
+    fn exact_song_name_wins_over_partial() {
+        let songs = [song("Santo Santo Santo"), song("Santo")];
+
+        assert_eq!(find_song(&songs, "santo").unwrap().path, "Santo");
+        assert_eq!(
+            find_song(&songs, "Santo, santo, santo!").unwrap().path,
+            "Santo Santo Santo"
+        );
+        assert_eq!(
+            find_song(&songs, "santo santo").unwrap().path,
+            "Santo Santo Santo"
+        );
+    }
+
+    #[test]
+    fn blank_titles_match_no_song() {
+        let songs = [song("Santo")];
+
+        assert!(find_song(&songs, "").is_none());
+        assert!(find_song(&songs, " - ").is_none());
+        assert!(find_song(&songs, "Sublime Gracia").is_none());
+    }
+}
@@ -2,7 +2,11 @@ use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use rfd::FileDialog;
 use slint::{ComponentHandle, Model, ModelRc, SharedString, Weak};
 use std::sync::Mutex;
-use std::{fs, path::Path, sync::Arc};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use ui::{FileItem, MainWindow, SongsState};
 
@@ -233,6 +237,36 @@ impl SongsManager {
         });
     }
 
+    /// Adds song files to the library, songs already in it keep their own text.
+    pub fn import_songs(&self, files: &[PathBuf]) {
+        let Some(window) = self.window.upgrade() else {
+            return;
+        };
+        let state = window.global::<SongsState>();
+        let mut songs_origin = self.songs_origin.lock().unwrap();
+        let mut songs_cache = self.songs_cache.lock().unwrap();
+
+        for path in files {
+            let name = path
+                .with_extension("")
+                .file_name()
+                .map(|f| f.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if songs_cache.iter().any(|s| s.path.as_str() == name) {
+                continue;
+            }
+
+            songs_origin.push(FileItem {
+                path: path.to_string_lossy().to_string().into(),
+                is_folder: false,
+            });
+            process_file_into_state(path, &state, &mut songs_cache);
+        }
+
+        self.data.save(&*songs_origin);
+        state.set_songs_origin(ModelRc::from(songs_origin.as_slice()));
+    }
+
     fn watch_and_process_folder(&self, folder: &Path, song_list: &mut Vec<SongItem>) {
         let window = self.window.unwrap();
         let state = window.global::<SongsState>();
@@ -3,7 +3,7 @@ use std::ops::{Deref, DerefMut};
 use serde::{Deserialize, Serialize};
 use ui::{FileItem, TextView, ViewFontData};
 
-use crate::manager::MediaItem;
+use crate::manager::{MediaItem, ScheduleFile};
 use crate::user_data::Save;
 
 macro_rules! impl_deref {
@@ -42,7 +42,20 @@ macro_rules! impl_deref {
 impl_deref! {
     FavoriteTexts(Vec<TextView>): "fav_texts",
     SourceSongs(Vec<FileItem>): "source_songs",
-    SourceMedia(Vec<MediaItem>): "source_media"
+    SourceMedia(Vec<MediaItem>): "source_media",
+    MediaFolders(Vec<FileItem>): "media_folders",
+    BrandingPresets(Vec<BrandingPreset>): "branding_presets"
+}
+
+/// The look of one service: its logo plus the background and fonts new content starts with.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct BrandingPreset {
+    pub name: String,
+    // id of the media item shown by the logo action
+    pub logo: Option<i32>,
+    pub background: MediaItem,
+    pub font: ViewFontData,
+    pub verse_font: ViewFontData,
 }
 
 #[derive(Clone, Default, Deserialize, Serialize)]
@@ -51,8 +64,29 @@ pub struct AppSettings {
     pub last_seen_version: Option<String>,
     pub content_font: Option<ViewFontData>,
     pub verse_font: Option<ViewFontData>,
+    #[serde(default)]
+    pub recent_schedules: Vec<String>,
+    #[serde(default)]
+    pub master_volume: Option<f32>,
+    #[serde(default)]
+    pub audio_device: Option<String>,
+    #[serde(default)]
+    pub fade_ms: Option<i32>,
+    // name of the branding preset in use
+    #[serde(default)]
+    pub branding: Option<String>,
 }
 
 impl Save for AppSettings {
     const NAME: &str = "settings";
 }
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct ScheduleAutosave {
+    pub path: Option<String>,
+    pub schedule: ScheduleFile,
+}
+
+impl Save for ScheduleAutosave {
+    const NAME: &str = "schedule_autosave";
+}